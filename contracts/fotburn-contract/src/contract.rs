@@ -1,25 +1,49 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
-    WasmMsg, WasmQuery, QueryRequest
+    attr, to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, Uint128,
+    WasmMsg, WasmQuery, QueryRequest, Order, Addr, Storage
 };
+use cw_storage_plus::Bound;
+use cw_utils::Expiration;
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Cw20QueryMsg};
 use cw20::{TokenInfoResponse};
 
 use crate::error::ContractError;
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, 
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, TierFill, SimulateResponse,
+    ReverseSimulateResponse, ReceiveMsg, Tier, PendingTiers, TiersResponse, FunderResponse, FundersResponse,
+    BurnerResponse, BurnersResponse, BurnRecordResponse, BurnHistoryResponse, BurnLimits, LimitsResponse,
+    FeeConfig, ReferralsResponse, PauseFlags, StatusResponse, OwnerProposal,
 };
 use crate::state::{
-    Config, CONFIG
+    Config, CONFIG, CONFIG_V0, FUNDERS, BurnerInfo, BurnRecord, BURNS, burners, EpochBurned,
+    EPOCH_BURNED, REFERRERS, REFERRALS, PauseState, PAUSE, OWNER_PROPOSAL
 };
 
 // Version info, for migration info
 const CONTRACT_NAME: &str = "fotburn";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const FOT_STEP:u128 = 10_000_000_000_000_000u128;
+// rate is 110 - tier, so the last tier pays nothing
+const MAX_FOT_TIER:u128 = 110u128;
+const MAX_TIERS:usize = 200;
+
+// fee on the bFOT paid out, in basis points
+const BPS_DENOMINATOR:u128 = 10_000u128;
+const MAX_FEE_BPS:u64 = 1_000u64;
+
+// rolling window of the per-address cap
+const ADDRESS_WINDOW:u64 = 86400u64;
+
+// uplines walked to rule out a referral loop, longer chains are refused.
+// resolve_referrer is the same in fotburn and bfotburn, change both together
+const MAX_REFERRAL_DEPTH:u32 = 32;
+
+// pagination of the list queries
+const DEFAULT_LIMIT:u32 = 10;
+const MAX_LIMIT:u32 = 30;
 
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -28,22 +52,53 @@ pub fn instantiate(
     _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let owner = msg
         .owner
         .map_or(Ok(info.sender), |o| deps.api.addr_validate(&o))?;
 
-    let config = Config {
+    let mut config = Config {
         owner: Some(owner),
         fot_token_address: deps.api.addr_validate(&msg.fot_token_address)?,
         bfot_token_address: deps.api.addr_validate(&msg.bfot_token_address)?,
         fot_burn_amount: Uint128::zero(),
         bfot_sent_amount: Uint128::zero(),
-        bfot_current_amount: Uint128::zero()
+        bfot_current_amount: Uint128::zero(),
+        fot_start_supply: Uint128::zero(),
+        tiers: msg.tiers.unwrap_or_else(default_tiers),
+        pending_tiers: None,
+        min_reserve: msg.min_reserve.unwrap_or_default(),
+        swaps_enabled: true,
+        funder_allowlist: None,
+        limits: msg.limits.unwrap_or_default(),
+        fee_bps: 0,
+        fee_treasury: None,
+        fee_kept: Uint128::zero(),
+        fee_referral_bps: 0
+    };
+    check_limits(&config.limits)?;
+    if let Some(fee) = msg.fee {
+        check_fee(&fee)?;
+        config.fee_bps = fee.bps;
+        config.fee_referral_bps = fee.referral_bps;
+        config.fee_treasury = fee.treasury.map(|treasury| deps.api.addr_validate(&treasury)).transpose()?;
+    }
+    if let Some(allowlist) = msg.funder_allowlist {
+        config.funder_allowlist = Some(allowlist
+            .iter()
+            .map(|address| deps.api.addr_validate(address))
+            .collect::<StdResult<Vec<_>>>()?);
+    }
+    check_tiers(&config.tiers)?;
+    config.fot_start_supply = match msg.fot_start_supply {
+        Some(fot_start_supply) => fot_start_supply,
+        None => query_fot_supply(deps.as_ref(), &config)?,
     };
     CONFIG.save(deps.storage, &config)?;
+    let guardian = msg.guardian.map(|g| deps.api.addr_validate(&g)).transpose()?;
+    PAUSE.save(deps.storage, &PauseState { guardian, paused: PauseFlags::default() })?;
 
     Ok(Response::default())
 }
@@ -51,39 +106,344 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { new_owner } => execute_update_config(deps, info, new_owner),
-        ExecuteMsg::Receive(msg) => try_receive(deps, info, msg),
+        ExecuteMsg::ProposeOwner { address, expiry } => execute_propose_owner(deps, env, info, address, expiry),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelProposal {} => execute_cancel_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::Receive(msg) => try_receive(deps, env, info, msg),
         ExecuteMsg::WithdrawAll {} => try_withdraw_all(deps, info),
+        ExecuteMsg::Withdraw { amount, recipient } => try_withdraw(deps, info, amount, recipient),
+        ExecuteMsg::UpdateFunders { allowlist } => execute_update_funders(deps, info, allowlist),
+        ExecuteMsg::UpdateLimits { limits } => execute_update_limits(deps, info, limits),
+        ExecuteMsg::UpdateFee { fee } => execute_update_fee(deps, info, fee),
+        ExecuteMsg::WithdrawFees { recipient } => execute_withdraw_fees(deps, info, recipient),
+        ExecuteMsg::UpdatePool { min_reserve, swaps_enabled }
+            => execute_update_pool(deps, info, min_reserve, swaps_enabled),
+        ExecuteMsg::UpdateTiers { tiers, effective_height }
+            => execute_update_tiers(deps, env, info, tiers, effective_height),
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::Pause { flags } => execute_set_paused(deps, info, flags, true),
+        ExecuteMsg::Unpause { flags } => execute_set_paused(deps, info, flags, false),
     }
 }
 
-//calculate fot_rate according to the fot_amount
-pub fn calc_fot_rate(
-    fot_amount: Uint128
-)-> Uint128 {
-    
-    let mut step = (fot_amount - Uint128::from(1u128)).checked_div(Uint128::from(FOT_STEP)).unwrap();
-    step = step + Uint128::from(1u128);
+//the original schedule, tiers of FOT_STEP paying 110 - tier, the last one nothing
+pub fn default_tiers() -> Vec<Tier> {
+    (1..=MAX_FOT_TIER)
+        .map(|tier| Tier {
+            threshold: Uint128::from(tier * FOT_STEP),
+            rate: Uint128::from(MAX_FOT_TIER - tier)
+        })
+        .collect()
+}
 
-    return Uint128::from(110u128) - step;
+//thresholds strictly rising, rates never rising with them
+fn check_tiers(tiers: &[Tier]) -> Result<(), ContractError> {
+    if tiers.is_empty() || tiers.len() > MAX_TIERS || tiers[0].threshold.is_zero() {
+        return Err(ContractError::InvalidTiers {})
+    }
+    if tiers.windows(2).any(|pair| pair[1].threshold <= pair[0].threshold || pair[1].rate > pair[0].rate) {
+        return Err(ContractError::InvalidTiers {})
+    }
+    Ok(())
 }
 
-//calculate bfot amount to send according to the fot_amount and fot_rate
+//promote the pending tier table once its height is reached
+pub fn apply_pending_tiers(cfg: &mut Config, height: u64) {
+    if cfg.pending_tiers.as_ref().is_some_and(|pending| pending.effective_height <= height) {
+        cfg.tiers = cfg.pending_tiers.take().unwrap().tiers;
+    }
+}
+
+//index of the tier holding the FOT position, tier i covers (threshold i-1, threshold i]
+pub fn calc_fot_tier(
+    tiers: &[Tier],
+    fot_position: Uint128
+) -> Result<usize, ContractError> {
+    if fot_position.is_zero() {
+        return Err(ContractError::NotEnoughFOT {})
+    }
+    let index = tiers.partition_point(|tier| tier.threshold < fot_position);
+    if index == tiers.len() {
+        return Err(ContractError::FotTierOutOfRange { tier: index as u128 + 1 })
+    }
+    Ok(index)
+}
+
+//bFOT paid for burning all the FOT below fot_position
+pub fn calc_fot_value(
+    tiers: &[Tier],
+    fot_position: Uint128
+) -> Result<Uint128, ContractError> {
+    let mut value = Uint128::zero();
+    let mut below = Uint128::zero();
+    for tier in tiers {
+        if fot_position <= below {
+            return Ok(value)
+        }
+        let amount = fot_position.min(tier.threshold) - below;
+        value = value.checked_add(amount.checked_mul(tier.rate)?)?;
+        below = tier.threshold;
+    }
+    if fot_position > below {
+        return Err(ContractError::FotTierOutOfRange { tier: tiers.len() as u128 + 1 })
+    }
+    Ok(value)
+}
+
+//calculate bfot amount for burning fot_amount from the top of fot_position,
+//the value below the position less the value below where the burn ends
 pub fn calc_bfot_amount(
-    fot_amount: Uint128,
-    fot_rate: Uint128
-)-> Uint128 {
-    
-    return fot_amount.checked_mul(fot_rate).unwrap();
+    tiers: &[Tier],
+    fot_position: Uint128,
+    fot_amount: Uint128
+) -> Result<Uint128, ContractError> {
+    if fot_amount.is_zero() {
+        return Ok(Uint128::zero())
+    }
+    if fot_amount > fot_position {
+        return Err(ContractError::NotEnoughFOT {})
+    }
+    Ok(calc_fot_value(tiers, fot_position)? - calc_fot_value(tiers, fot_position - fot_amount)?)
+}
+
+//split burning fot_amount from the top of fot_position into the tiers it crosses
+pub fn calc_tier_fills(
+    tiers: &[Tier],
+    fot_position: Uint128,
+    fot_amount: Uint128
+) -> Result<Vec<TierFill>, ContractError> {
+    if fot_amount > fot_position {
+        return Err(ContractError::NotEnoughFOT {})
+    }
+    let mut fills = vec![];
+    if fot_amount.is_zero() {
+        return Ok(fills)
+    }
+    let mut fot_position = fot_position;
+    let mut amount = fot_amount;
+    for index in (0..=calc_fot_tier(tiers, fot_position)?).rev() {
+        let below = if index == 0 { Uint128::zero() } else { tiers[index - 1].threshold };
+        let rate = tiers[index].rate;
+        let fot_tier_amount = amount.min(fot_position - below);
+        fills.push(TierFill {
+            tier: index as u128 + 1,
+            rate,
+            fot_amount: fot_tier_amount,
+            bfot_amount: fot_tier_amount.checked_mul(rate)?
+        });
+        fot_position -= fot_tier_amount;
+        amount -= fot_tier_amount;
+        if amount.is_zero() {
+            break
+        }
+    }
+    Ok(fills)
+}
+
+//least fot amount to burn from the top of fot_position for bfot_amount, the burn ends
+//at the highest position whose value is still within the value left after it
+pub fn calc_fot_amount(
+    tiers: &[Tier],
+    fot_position: Uint128,
+    bfot_amount: Uint128
+) -> Result<Uint128, ContractError> {
+    if bfot_amount.is_zero() {
+        return Ok(Uint128::zero())
+    }
+    let value = calc_fot_value(tiers, fot_position)?;
+    if value < bfot_amount {
+        return Err(ContractError::NotEnoughFOT {})
+    }
+    let target = value - bfot_amount;
+
+    let mut value_below = Uint128::zero();
+    let mut below = Uint128::zero();
+    for tier in tiers {
+        let tier_value = (tier.threshold - below).checked_mul(tier.rate)?;
+        if value_below + tier_value > target {
+            //zero rate tiers never pass the target, rate is positive here
+            let bottom = below + (target - value_below) / tier.rate;
+            return Ok(fot_position - bottom)
+        }
+        value_below += tier_value;
+        below = tier.threshold;
+    }
+    //unreachable while the value at fot_position is above the target
+    Err(ContractError::NotEnoughFOT {})
+}
+
+//position the tiers are priced from, counted down by the FOT burned here only
+pub fn calc_fot_position(
+    cfg: &Config
+) -> Result<Uint128, ContractError> {
+    cfg.fot_start_supply.checked_sub(cfg.fot_burn_amount).map_err(|_| ContractError::NotEnoughFOT {})
+}
+
+fn query_fot_supply(deps: Deps, cfg: &Config) -> StdResult<Uint128> {
+    let fot_token_info: TokenInfoResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: cfg.fot_token_address.clone().into(),
+            msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
+        }))?;
+    Ok(fot_token_info.total_supply)
+}
+
+//fee taken from an output amount, rounded down
+pub fn calc_fee(amount: Uint128, fee_bps: u64) -> Uint128 {
+    amount.multiply_ratio(fee_bps, BPS_DENOMINATOR)
+}
+
+//least output before the fee that leaves at least amount after it
+pub fn calc_amount_before_fee(amount: Uint128, fee_bps: u64) -> Uint128 {
+    if amount.is_zero() {
+        return amount
+    }
+    //amount after the fee is ceil(gross * (1 - fee)), so gross * (1 - fee) must pass amount - 1
+    (amount - Uint128::from(1u128)).multiply_ratio(BPS_DENOMINATOR, BPS_DENOMINATOR - fee_bps as u128)
+        + Uint128::from(1u128)
+}
+
+fn check_fee(fee: &FeeConfig) -> Result<(), ContractError> {
+    if fee.bps > MAX_FEE_BPS {
+        return Err(ContractError::FeeTooHigh { max_bps: MAX_FEE_BPS })
+    }
+    if fee.referral_bps as u128 > BPS_DENOMINATOR {
+        return Err(ContractError::ReferralTooHigh { max_bps: BPS_DENOMINATOR as u64 })
+    }
+    Ok(())
+}
+
+//FOT burned by the address within the rolling window ending now
+fn calc_address_window_burned(storage: &dyn Storage, burner: &Addr, now: u64) -> StdResult<Uint128> {
+    let since = now.saturating_sub(ADDRESS_WINDOW);
+    let mut burned = Uint128::zero();
+    for item in BURNS.prefix(burner).range(storage, None, None, Order::Descending) {
+        let (_, record) = item?;
+        if record.time <= since {
+            break
+        }
+        burned += record.fot_amount;
+    }
+    Ok(burned)
+}
+
+//FOT burned so far in the epoch now falls in
+fn calc_epoch_burned(storage: &dyn Storage, limits: &BurnLimits, now: u64) -> StdResult<(u64, Uint128)> {
+    if limits.epoch_length == 0 {
+        return Ok((0, Uint128::zero()))
+    }
+    let epoch = now / limits.epoch_length;
+    let burned = EPOCH_BURNED.may_load(storage)?
+        .filter(|epoch_burned| epoch_burned.epoch == epoch)
+        .map(|epoch_burned| epoch_burned.burned)
+        .unwrap_or_default();
+    Ok((epoch, burned))
+}
+
+//check the burn against the caps and count it into the epoch
+fn check_burn_limits(
+    storage: &mut dyn Storage,
+    limits: &BurnLimits,
+    burner: &Addr,
+    now: u64,
+    fot_amount: Uint128
+) -> Result<(), ContractError> {
+    if let Some(max_per_tx) = limits.max_per_tx {
+        if fot_amount > max_per_tx {
+            return Err(ContractError::TxLimitExceeded { max_per_tx })
+        }
+    }
+    if let Some(max_per_address) = limits.max_per_address {
+        let remaining = max_per_address.saturating_sub(calc_address_window_burned(storage, burner, now)?);
+        if fot_amount > remaining {
+            return Err(ContractError::AddressLimitExceeded { remaining })
+        }
+    }
+    if let Some(max_per_epoch) = limits.max_per_epoch {
+        let (epoch, burned) = calc_epoch_burned(storage, limits, now)?;
+        let remaining = max_per_epoch.saturating_sub(burned);
+        if fot_amount > remaining {
+            return Err(ContractError::EpochLimitExceeded { remaining })
+        }
+        EPOCH_BURNED.save(storage, &EpochBurned { epoch, burned: burned + fot_amount })?;
+    }
+    Ok(())
+}
+
+fn check_limits(limits: &BurnLimits) -> Result<(), ContractError> {
+    if limits.max_per_epoch.is_some() && limits.epoch_length == 0 {
+        return Err(ContractError::InvalidLimits {})
+    }
+    Ok(())
+}
+
+//add a burn to the burner's totals and history
+fn record_burn(storage: &mut dyn Storage, burner: &Addr, record: BurnRecord) -> StdResult<()> {
+    let info = burners().update(storage, burner, |info| -> StdResult<_> {
+        let mut info = info.unwrap_or(BurnerInfo {
+            fot_burned: Uint128::zero(),
+            bfot_received: Uint128::zero(),
+            burn_count: 0,
+            first_burn: record.time,
+            last_burn: record.time
+        });
+        info.fot_burned += record.fot_amount;
+        info.bfot_received += record.bfot_amount;
+        info.burn_count += 1;
+        info.last_burn = record.time;
+        Ok(info)
+    })?;
+    BURNS.save(storage, (burner, info.burn_count), &record)
+}
+
+//the referrer of a referred address, linking the given one on its first referred swap
+fn resolve_referrer(
+    storage: &mut dyn Storage,
+    referred: &Addr,
+    referrer: Option<Addr>
+) -> Result<Option<Addr>, ContractError> {
+    //an address keeps its first referrer, one passed on a later swap is ignored
+    if let Some(existing) = REFERRERS.may_load(storage, referred)? {
+        return Ok(Some(existing))
+    }
+    let referrer = match referrer {
+        Some(referrer) => referrer,
+        None => return Ok(None),
+    };
+    if referrer == *referred {
+        return Err(ContractError::SelfReferral {})
+    }
+    //walk up the referrer's chain, the referred address showing up would close a loop
+    let mut next = REFERRERS.may_load(storage, &referrer)?;
+    let mut depth = 0u32;
+    while let Some(upline) = next {
+        if upline == *referred {
+            return Err(ContractError::ReferralLoop {})
+        }
+        depth += 1;
+        if depth > MAX_REFERRAL_DEPTH {
+            return Err(ContractError::ReferralChainTooDeep { max_depth: MAX_REFERRAL_DEPTH })
+        }
+        next = REFERRERS.may_load(storage, &upline)?;
+    }
+    REFERRERS.save(storage, referred, &referrer)?;
+    REFERRALS.update(storage, &referrer, |info| -> StdResult<_> {
+        let mut info = info.unwrap_or_default();
+        info.referred_count += 1;
+        Ok(info)
+    })?;
+    Ok(Some(referrer))
 }
 
 pub fn try_receive(
     deps: DepsMut, 
+    env: Env,
     info: MessageInfo, 
     wrapper: Cw20ReceiveMsg
 ) -> Result<Response, ContractError> {
@@ -104,76 +464,190 @@ pub fn try_receive(
     //     }
     // }
 
-    let fot_token_info: TokenInfoResponse =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: cfg.fot_token_address.clone().into(),
-            msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
-        }))?;
-
-    let fot_left_amount = Uint128::from(fot_token_info.total_supply);
     let user_addr = &deps.api.addr_validate(&wrapper.sender)?;
 
     if info.sender == cfg.fot_token_address {
+        if !cfg.swaps_enabled {
+            return Err(ContractError::SwapsDisabled {})
+        }
+        if PAUSE.may_load(deps.storage)?.unwrap_or_default().paused.swaps {
+            return Err(ContractError::Paused { action: "swaps".to_string() })
+        }
         // Not possible code
         // if fot_left_amount < wrapper.amount {
         //     return Err(ContractError::NotEnoughFOT {});
         // }
 
-        let fot_received_amount = wrapper.amount;
-        let mut bfot_send_amount = Uint128::zero();
-        let mut amount = wrapper.amount;
-        let mut fot_amount = fot_left_amount;
-
-        while amount > Uint128::zero() {
-            let mut sliceamount = fot_amount.checked_rem(Uint128::from(FOT_STEP)).unwrap();
-            if sliceamount == Uint128::zero() {
-                sliceamount = Uint128::from(FOT_STEP);
+        let mut allow_partial = false;
+        let mut min_bfot_out = None;
+        let mut recipient = user_addr.clone();
+        let mut referrer = None;
+        if !wrapper.msg.is_empty() {
+            match from_binary(&wrapper.msg)? {
+                ReceiveMsg::Fot { allow_partial: partial } => allow_partial = partial,
+                ReceiveMsg::Bfot {} | ReceiveMsg::Fund {} => return Err(ContractError::InvalidInput {}),
+                ReceiveMsg::Burn { min_bfot_out: min, recipient: to, deadline, allow_partial: partial, referrer: by } => {
+                    if let Some(deadline) = deadline {
+                        if deadline.is_expired(&env.block) {
+                            return Err(ContractError::DeadlinePassed { deadline })
+                        }
+                    }
+                    if let Some(to) = to {
+                        recipient = deps.api.addr_validate(&to)?;
+                    }
+                    if let Some(by) = by {
+                        referrer = Some(deps.api.addr_validate(&by)?);
+                    }
+                    min_bfot_out = min;
+                    allow_partial = partial;
+                }
             }
-            if sliceamount > amount {
-                sliceamount = amount;
-            }
-            bfot_send_amount = bfot_send_amount + calc_bfot_amount(sliceamount, calc_fot_rate(fot_amount));
-            fot_amount = fot_amount - sliceamount;
-            amount = amount - sliceamount;
         }
+
+        apply_pending_tiers(&mut cfg, env.block.height);
+        let fot_position = calc_fot_position(&cfg)?;
+        let mut fot_received_amount = wrapper.amount;
+        let mut bfot_send_amount = calc_bfot_amount(&cfg.tiers, fot_position, fot_received_amount)?;
+
         // bfot_send_amount = amount.checked_div(Uint128::from(10u128)).unwrap();
         if cfg.bfot_current_amount < bfot_send_amount {
-            return Err(ContractError::NotEnoughbFOT {})
+            if !allow_partial {
+                return Err(ContractError::NotEnoughbFOT { bfot_accept_amount: cfg.bfot_current_amount })
+            }
+            //the most FOT the pool pays for is one less than the least FOT paying more than the pool
+            fot_received_amount = calc_fot_amount(&cfg.tiers, fot_position, cfg.bfot_current_amount + Uint128::from(1u128))?
+                - Uint128::from(1u128);
+            if fot_received_amount.is_zero() {
+                return Err(ContractError::NotEnoughbFOT { bfot_accept_amount: cfg.bfot_current_amount })
+            }
+            bfot_send_amount = calc_bfot_amount(&cfg.tiers, fot_position, fot_received_amount)?;
+        }
+        //the fee comes out of what the pool pays
+        let fee_amount = calc_fee(bfot_send_amount, cfg.fee_bps);
+        let bfot_pool_amount = bfot_send_amount;
+        bfot_send_amount -= fee_amount;
+        if let Some(min_bfot_out) = min_bfot_out {
+            if bfot_send_amount < min_bfot_out {
+                return Err(ContractError::MinBfotOut { bfot_out: bfot_send_amount, min_bfot_out })
+            }
         }
+        let fot_refund_amount = wrapper.amount - fot_received_amount;
+        check_burn_limits(deps.storage, &cfg.limits, user_addr, env.block.time.seconds(), fot_received_amount)?;
+
+        //the referrer's share comes out of the fee
+        let referrer = resolve_referrer(deps.storage, user_addr, referrer)?;
+        let referral_amount = match &referrer {
+            Some(referrer) => {
+                let referral_amount = calc_fee(fee_amount, cfg.fee_referral_bps);
+                REFERRALS.update(deps.storage, referrer, |info| -> StdResult<_> {
+                    let mut info = info.unwrap_or_default();
+                    info.reward_amount = info.reward_amount.checked_add(referral_amount)?;
+                    Ok(info)
+                })?;
+                referral_amount
+            }
+            None => Uint128::zero(),
+        };
+        let fee_amount = fee_amount - referral_amount;
+
         cfg.fot_burn_amount = cfg.fot_burn_amount + fot_received_amount;
-        cfg.bfot_sent_amount = cfg.bfot_sent_amount + bfot_send_amount;
-        cfg.bfot_current_amount = cfg.bfot_current_amount - bfot_send_amount;
+        cfg.bfot_sent_amount = cfg.bfot_sent_amount + bfot_pool_amount;
+        cfg.bfot_current_amount = cfg.bfot_current_amount - bfot_pool_amount;
+        if cfg.fee_treasury.is_none() {
+            cfg.fee_kept += fee_amount;
+        }
 
         CONFIG.save(deps.storage, &cfg)?;
+
+        record_burn(deps.storage, user_addr, BurnRecord {
+            fot_amount: fot_received_amount,
+            bfot_amount: bfot_send_amount,
+            start_tier: calc_fot_tier(&cfg.tiers, fot_position)? as u128 + 1,
+            end_tier: calc_fot_tier(&cfg.tiers, fot_position - fot_received_amount + Uint128::from(1u128))? as u128 + 1,
+            time: env.block.time.seconds()
+        })?;
         
-        //send bfot_send_amount, burn fot_received_amount
-        return Ok(Response::new()
+        //send bfot_send_amount, burn fot_received_amount, give back the FOT left unpaid
+        let mut res = Response::new()
             .add_message(WasmMsg::Execute {
-                contract_addr: cfg.bfot_token_address.into(),
+                contract_addr: cfg.bfot_token_address.clone().into(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: user_addr.into(),
+                    recipient: recipient.clone().into(),
                     amount: bfot_send_amount,
                 })?,
-            })
+            });
+        if let Some(referrer) = &referrer {
+            if !referral_amount.is_zero() {
+                res = res.add_message(WasmMsg::Execute {
+                    contract_addr: cfg.bfot_token_address.clone().into(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: referrer.into(),
+                        amount: referral_amount,
+                    })?,
+                });
+            }
+        }
+        if let Some(treasury) = &cfg.fee_treasury {
+            if !fee_amount.is_zero() {
+                res = res.add_message(WasmMsg::Execute {
+                    contract_addr: cfg.bfot_token_address.into(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: treasury.into(),
+                        amount: fee_amount,
+                    })?,
+                });
+            }
+        }
+        res = res
             .add_message(WasmMsg::Execute {
-                contract_addr: cfg.fot_token_address.into(),
+                contract_addr: cfg.fot_token_address.clone().into(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Burn {
                     amount: fot_received_amount,
                 })?,
-            })
+            });
+        if !fot_refund_amount.is_zero() {
+            res = res.add_message(WasmMsg::Execute {
+                contract_addr: cfg.fot_token_address.into(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: user_addr.into(),
+                    amount: fot_refund_amount,
+                })?,
+            });
+        }
+        return Ok(res
             .add_attributes(vec![
                 attr("action", "send_bfot_burn_fot"),
                 attr("address", user_addr),
+                attr("recipient", recipient),
                 attr("bfot_amount", bfot_send_amount),
+                attr("fee_amount", fee_amount),
+                attr("referrer", referrer.map_or_else(String::new, |r| r.into())),
+                attr("referral_amount", referral_amount),
                 attr("fot_amount", fot_received_amount),
+                attr("fot_refund_amount", fot_refund_amount),
             ]));
 
     } else if info.sender == cfg.bfot_token_address {
-        //Just receive in contract cache and update config
+        //only explicit deposits from allowed funders go into the pool
+        if wrapper.msg.is_empty() || !matches!(from_binary(&wrapper.msg)?, ReceiveMsg::Fund {}) {
+            return Err(ContractError::FundPayloadRequired {})
+        }
+        if let Some(allowlist) = &cfg.funder_allowlist {
+            if !allowlist.contains(user_addr) {
+                return Err(ContractError::FunderNotAllowed { address: user_addr.to_string() })
+            }
+        }
+
         cfg.bfot_current_amount = cfg.bfot_current_amount + wrapper.amount;
         CONFIG.save(deps.storage, &cfg)?;
+        FUNDERS.update(deps.storage, user_addr, |amount| -> StdResult<_> {
+            Ok(amount.unwrap_or_default().checked_add(wrapper.amount)?)
+        })?;
 
         return Ok(Response::new()
             .add_attributes(vec![
@@ -200,39 +674,150 @@ pub fn check_owner(
     Ok(Response::new().add_attribute("action", "check_owner"))
 }
 
-pub fn execute_update_config(
+//the guardian only ever toggles swaps, the owner can do the same
+pub fn check_guardian(
+    deps: &DepsMut,
+    info: &MessageInfo
+) -> Result<PauseState, ContractError> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    if pause.guardian.as_ref() != Some(&info.sender) {
+        check_owner(deps, info)?;
+    }
+    Ok(pause)
+}
+
+pub fn execute_update_guardian(
     deps: DepsMut,
     info: MessageInfo,
-    new_owner: Option<String>,
+    guardian: Option<String>
 ) -> Result<Response, ContractError> {
-    // authorize owner
     check_owner(&deps, &info)?;
-    
-    //test code for checking if check_owner works well
-    // return Err(ContractError::InvalidInput {});
-    // if owner some validated to addr, otherwise set to none
-    let mut tmp_owner = None;
-    if let Some(addr) = new_owner {
-        tmp_owner = Some(deps.api.addr_validate(&addr)?)
+
+    let guardian = guardian.map(|g| deps.api.addr_validate(&g)).transpose()?;
+    let mut pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    pause.guardian = guardian;
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new().add_attribute("action", "update_guardian"))
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    flags: PauseFlags,
+    paused: bool
+) -> Result<Response, ContractError> {
+    let mut pause = check_guardian(&deps, &info)?;
+    if flags.swaps {
+        pause.paused.swaps = paused;
+    }
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", if paused { "pause" } else { "unpause" }),
+        attr("swaps", pause.paused.swaps.to_string()),
+    ]))
+}
+
+pub fn execute_propose_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expiry: Option<Expiration>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    let owner = deps.api.addr_validate(&address)?;
+    let expiry = expiry.unwrap_or_default();
+    if expiry.is_expired(&env.block) {
+        return Err(ContractError::OwnerProposalExpired { expiry })
+    }
+    OWNER_PROPOSAL.save(deps.storage, &OwnerProposal { owner: owner.clone(), expiry })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+        attr("expiry", expiry.to_string()),
+    ]))
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let proposal = OWNER_PROPOSAL.may_load(deps.storage)?.ok_or(ContractError::NoOwnerProposal {})?;
+    if info.sender != proposal.owner {
+        return Err(ContractError::Unauthorized {})
+    }
+    if proposal.expiry.is_expired(&env.block) {
+        return Err(ContractError::OwnerProposalExpired { expiry: proposal.expiry })
     }
 
     CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
-        exists.owner = tmp_owner;
+        exists.owner = Some(proposal.owner);
         Ok(exists)
     })?;
+    OWNER_PROPOSAL.remove(deps.storage);
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender),
+    ]))
 }
 
+pub fn execute_cancel_proposal(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    if OWNER_PROPOSAL.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoOwnerProposal {})
+    }
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_proposal"))
+}
+
+pub fn execute_renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.owner = None;
+        Ok(exists)
+    })?;
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
+}
+
+
+//bFOT the owner can take out, all of it once swaps are off
+pub fn calc_withdrawable(cfg: &Config) -> Uint128 {
+    if cfg.swaps_enabled {
+        cfg.bfot_current_amount.saturating_sub(cfg.min_reserve)
+    } else {
+        cfg.bfot_current_amount
+    }
+}
 
 pub fn try_withdraw_all(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
 
     check_owner(&deps, &info)?;
     let mut cfg = CONFIG.load(deps.storage)?;
     
-    let bfot_current_amount = cfg.bfot_current_amount;
+    //everything above the reserve while swaps run
+    let bfot_current_amount = calc_withdrawable(&cfg);
+    if bfot_current_amount.is_zero() {
+        return Err(ContractError::BelowReserve { available: bfot_current_amount })
+    }
     let bfot_token_address = cfg.bfot_token_address.clone();
-    cfg.bfot_current_amount = Uint128::zero();
+    cfg.bfot_current_amount -= bfot_current_amount;
 
     CONFIG.save(deps.storage, &cfg)?;
 
@@ -256,26 +841,412 @@ pub fn try_withdraw_all(deps: DepsMut, info: MessageInfo) -> Result<Response, Co
         ]));
 }
 
+pub fn try_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    recipient: Option<String>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidInput {})
+    }
+    let available = calc_withdrawable(&cfg);
+    if amount > available {
+        return Err(ContractError::BelowReserve { available })
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+
+    cfg.bfot_current_amount -= amount;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: cfg.bfot_token_address.into(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.clone().into(),
+                amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            attr("action", "bfot_withdraw"),
+            attr("address", info.sender),
+            attr("recipient", recipient),
+            attr("bfot_amount", amount),
+        ]))
+}
+
+pub fn execute_update_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_reserve: Option<Uint128>,
+    swaps_enabled: Option<bool>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    let cfg = CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        if let Some(min_reserve) = min_reserve {
+            exists.min_reserve = min_reserve;
+        }
+        if let Some(swaps_enabled) = swaps_enabled {
+            exists.swaps_enabled = swaps_enabled;
+        }
+        Ok(exists)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pool"),
+        attr("min_reserve", cfg.min_reserve),
+        attr("swaps_enabled", cfg.swaps_enabled.to_string()),
+    ]))
+}
+
+pub fn execute_update_funders(
+    deps: DepsMut,
+    info: MessageInfo,
+    allowlist: Option<Vec<String>>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    let allowlist = allowlist
+        .map(|addresses| {
+            addresses
+                .iter()
+                .map(|address| deps.api.addr_validate(address))
+                .collect::<StdResult<Vec<_>>>()
+        })
+        .transpose()?;
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.funder_allowlist = allowlist;
+        Ok(exists)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "update_funders"))
+}
+
+pub fn execute_update_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    limits: BurnLimits
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    check_limits(&limits)?;
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.limits = limits;
+        Ok(exists)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "update_limits"))
+}
+
+pub fn execute_update_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee: FeeConfig
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    check_fee(&fee)?;
+
+    let treasury = fee.treasury.as_ref().map(|treasury| deps.api.addr_validate(treasury)).transpose()?;
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.fee_bps = fee.bps;
+        exists.fee_referral_bps = fee.referral_bps;
+        exists.fee_treasury = treasury;
+        Ok(exists)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_fee"),
+        attr("fee_bps", fee.bps.to_string()),
+        attr("referral_bps", fee.referral_bps.to_string()),
+    ]))
+}
+
+pub fn execute_withdraw_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: Option<String>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    let amount = cfg.fee_kept;
+    if amount.is_zero() {
+        return Err(ContractError::NoFees {})
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+    cfg.fee_kept = Uint128::zero();
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: cfg.bfot_token_address.into(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.clone().into(),
+                amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            attr("action", "withdraw_fees"),
+            attr("recipient", recipient),
+            attr("bfot_amount", amount),
+        ]))
+}
+
+pub fn execute_update_tiers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tiers: Vec<Tier>,
+    effective_height: u64
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    check_tiers(&tiers)?;
+    if effective_height <= env.block.height {
+        return Err(ContractError::TiersNotInFuture { effective_height })
+    }
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    //a table already due goes live before it would be replaced
+    apply_pending_tiers(&mut cfg, env.block.height);
+    cfg.pending_tiers = Some(PendingTiers { tiers, effective_height });
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_tiers"),
+        attr("effective_height", effective_height.to_string()),
+    ]))
+}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Status {} => to_binary(&query_status(deps)?),
+        QueryMsg::Simulate { fot_amount } => to_binary(&query_simulate(deps, env, fot_amount)?),
+        QueryMsg::ReverseSimulate { bfot_amount } => to_binary(&query_reverse_simulate(deps, env, bfot_amount)?),
+        QueryMsg::Tiers {} => to_binary(&query_tiers(deps, env)?),
+        QueryMsg::Funders { start_after, limit } => to_binary(&query_funders(deps, start_after, limit)?),
+        QueryMsg::Burner { address } => to_binary(&query_burner(deps, address)?),
+        QueryMsg::Limits { address } => to_binary(&query_limits(deps, env, address)?),
+        QueryMsg::BurnHistory { address, start_after, limit }
+            => to_binary(&query_burn_history(deps, address, start_after, limit)?),
+        QueryMsg::TopBurners { start_after, limit } => to_binary(&query_top_burners(deps, start_after, limit)?),
+        QueryMsg::Referrals { referrer } => to_binary(&query_referrals(deps, referrer)?),
     }
 }
 
+pub fn query_status(deps: Deps) -> StdResult<StatusResponse> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    Ok(StatusResponse {
+        guardian: pause.guardian.map(|g| g.into()),
+        paused: pause.paused
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let cfg = CONFIG.load(deps.storage)?;
+    let fot_position = calc_fot_position(&cfg).map_err(to_std_err)?;
     Ok(ConfigResponse {
         owner: cfg.owner.map(|o| o.into()),
         fot_token_address: cfg.fot_token_address.into(),
         bfot_token_address: cfg.bfot_token_address.into(),
         fot_burn_amount: cfg.fot_burn_amount,
         bfot_sent_amount: cfg.bfot_sent_amount,
-        bfot_current_amount: cfg.bfot_current_amount
+        bfot_current_amount: cfg.bfot_current_amount,
+        fot_start_supply: cfg.fot_start_supply,
+        fot_position,
+        min_reserve: cfg.min_reserve,
+        swaps_enabled: cfg.swaps_enabled,
+        funder_allowlist: cfg.funder_allowlist.map(|allowlist| allowlist.into_iter().map(|a| a.into()).collect()),
+        fee_bps: cfg.fee_bps,
+        fee_treasury: cfg.fee_treasury.map(|t| t.into()),
+        fee_kept: cfg.fee_kept,
+        fee_referral_bps: cfg.fee_referral_bps,
+        pending_owner: OWNER_PROPOSAL.may_load(deps.storage)?
+    })
+}
+
+fn to_std_err(err: ContractError) -> StdError {
+    match err {
+        ContractError::Std(err) => err,
+        err => StdError::generic_err(err.to_string()),
+    }
+}
+
+pub fn query_simulate(deps: Deps, env: Env, fot_amount: Uint128) -> StdResult<SimulateResponse> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    apply_pending_tiers(&mut cfg, env.block.height);
+    let fot_position = calc_fot_position(&cfg).map_err(to_std_err)?;
+    let bfot_pool_amount = calc_bfot_amount(&cfg.tiers, fot_position, fot_amount).map_err(to_std_err)?;
+    let fee_amount = calc_fee(bfot_pool_amount, cfg.fee_bps);
+    Ok(SimulateResponse {
+        bfot_out: bfot_pool_amount - fee_amount,
+        fee_amount,
+        tiers: calc_tier_fills(&cfg.tiers, fot_position, fot_amount).map_err(to_std_err)?,
+        pool_sufficient: bfot_pool_amount <= cfg.bfot_current_amount
+    })
+}
+
+pub fn query_reverse_simulate(deps: Deps, env: Env, bfot_amount: Uint128) -> StdResult<ReverseSimulateResponse> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    apply_pending_tiers(&mut cfg, env.block.height);
+    let fot_position = calc_fot_position(&cfg).map_err(to_std_err)?;
+    let bfot_pool_amount = calc_amount_before_fee(bfot_amount, cfg.fee_bps);
+    let fot_amount = calc_fot_amount(&cfg.tiers, fot_position, bfot_pool_amount).map_err(to_std_err)?;
+    let bfot_pool_amount = calc_bfot_amount(&cfg.tiers, fot_position, fot_amount).map_err(to_std_err)?;
+    let fee_amount = calc_fee(bfot_pool_amount, cfg.fee_bps);
+    Ok(ReverseSimulateResponse {
+        fot_amount,
+        bfot_out: bfot_pool_amount - fee_amount,
+        fee_amount,
+        pool_sufficient: bfot_pool_amount <= cfg.bfot_current_amount
     })
 }
 
+pub fn query_tiers(deps: Deps, env: Env) -> StdResult<TiersResponse> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    apply_pending_tiers(&mut cfg, env.block.height);
+    Ok(TiersResponse {
+        tiers: cfg.tiers,
+        pending: cfg.pending_tiers
+    })
+}
+
+pub fn query_funders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<FundersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|address| deps.api.addr_validate(&address)).transpose()?;
+    let min = start_after.map(|address| Bound::exclusive(address.as_bytes()));
+
+    let funders = FUNDERS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(address, amount)| FunderResponse { address: address.into(), amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(FundersResponse { funders })
+}
+
+pub fn query_limits(deps: Deps, env: Env, address: String) -> StdResult<LimitsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let cfg = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    let address_remaining = match cfg.limits.max_per_address {
+        Some(max_per_address) => Some(max_per_address.saturating_sub(
+            calc_address_window_burned(deps.storage, &address, now)?
+        )),
+        None => None
+    };
+    let epoch_remaining = match cfg.limits.max_per_epoch {
+        Some(max_per_epoch) => Some(max_per_epoch.saturating_sub(
+            calc_epoch_burned(deps.storage, &cfg.limits, now)?.1
+        )),
+        None => None
+    };
+
+    Ok(LimitsResponse {
+        limits: cfg.limits,
+        address_remaining,
+        epoch_remaining
+    })
+}
+
+fn to_burner_response(address: Addr, info: BurnerInfo) -> BurnerResponse {
+    BurnerResponse {
+        address: address.into(),
+        fot_burned: info.fot_burned,
+        bfot_received: info.bfot_received,
+        burn_count: info.burn_count,
+        first_burn: info.first_burn,
+        last_burn: info.last_burn
+    }
+}
+
+pub fn query_burner(deps: Deps, address: String) -> StdResult<BurnerResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let info = burners().load(deps.storage, &address)?;
+    Ok(to_burner_response(address, info))
+}
+
+pub fn query_referrals(deps: Deps, referrer: String) -> StdResult<ReferralsResponse> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    let info = REFERRALS.may_load(deps.storage, &referrer)?.unwrap_or_default();
+    Ok(ReferralsResponse {
+        referrer: referrer.into(),
+        referred_count: info.referred_count,
+        reward_amount: info.reward_amount
+    })
+}
+
+pub fn query_burn_history(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+) -> StdResult<BurnHistoryResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive_int);
+
+    let burns = BURNS
+        .prefix(&address)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, record)| BurnRecordResponse {
+            id,
+            fot_amount: record.fot_amount,
+            bfot_amount: record.bfot_amount,
+            start_tier: record.start_tier,
+            end_tier: record.end_tier,
+            time: record.time
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(BurnHistoryResponse { address: address.into(), burns })
+}
+
+pub fn query_top_burners(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<BurnersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|address| deps.api.addr_validate(&address)).transpose()?;
+
+    //index keys are (fot burned, address), so the bound needs the amount of start_after
+    let max = match start_after {
+        Some(address) => {
+            let info = burners().load(deps.storage, &address)?;
+            let mut key = burners().idx.fot_burned.index_key(info.fot_burned.u128().to_be_bytes().to_vec());
+            key.extend_from_slice(address.as_bytes());
+            Some(Bound::exclusive(key))
+        }
+        None => None
+    };
+
+    let burners = burners().idx.fot_burned
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(address, info)| to_burner_response(address, info)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(BurnersResponse { burners })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let version = get_contract_version(deps.storage)?;
@@ -284,5 +1255,694 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
             previous_contract: version.contract,
         });
     }
+
+    // older deployments priced off the live supply, so start counting down from where it is now
+    let old = CONFIG_V0.load(deps.storage)?;
+    let mut cfg = Config {
+        owner: old.owner,
+        fot_token_address: old.fot_token_address,
+        bfot_token_address: old.bfot_token_address,
+        fot_burn_amount: old.fot_burn_amount,
+        bfot_sent_amount: old.bfot_sent_amount,
+        bfot_current_amount: old.bfot_current_amount,
+        fot_start_supply: Uint128::zero(),
+        tiers: old.tiers.unwrap_or_else(default_tiers),
+        pending_tiers: old.pending_tiers,
+        min_reserve: old.min_reserve.unwrap_or_default(),
+        swaps_enabled: old.swaps_enabled.unwrap_or(true),
+        funder_allowlist: old.funder_allowlist,
+        limits: old.limits.unwrap_or_default(),
+        fee_bps: old.fee_bps.unwrap_or_default(),
+        fee_treasury: old.fee_treasury,
+        fee_kept: old.fee_kept.unwrap_or_default(),
+        fee_referral_bps: old.fee_referral_bps.unwrap_or_default()
+    };
+    cfg.fot_start_supply = match old.fot_start_supply {
+        Some(fot_start_supply) => fot_start_supply,
+        None => query_fot_supply(deps.as_ref(), &cfg)? + cfg.fot_burn_amount,
+    };
+    CONFIG.save(deps.storage, &cfg)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{ContractResult, CosmosMsg, SystemResult};
+
+    // xorshift64, each test seeds its own so failing inputs replay
+    struct Rng(u64);
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, max: u128) -> u128 {
+            ((self.next() as u128) << 64 | self.next() as u128) % max
+        }
+    }
+
+    // the per-slice loop the closed form replaces
+    fn reference_bfot_amount(fot_supply: u128, fot_amount: u128) -> Option<u128> {
+        // keep the expected panics of the reference quiet
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        let result = std::panic::catch_unwind(|| {
+            let rate = |fot_amount: Uint128| {
+                Uint128::from(110u128) - ((fot_amount - Uint128::from(1u128)) / Uint128::from(FOT_STEP) + Uint128::from(1u128))
+            };
+            let mut bfot_send_amount = Uint128::zero();
+            let mut amount = Uint128::from(fot_amount);
+            let mut fot_amount = Uint128::from(fot_supply);
+            while amount > Uint128::zero() {
+                let mut sliceamount = fot_amount.checked_rem(Uint128::from(FOT_STEP)).unwrap();
+                if sliceamount == Uint128::zero() {
+                    sliceamount = Uint128::from(FOT_STEP);
+                }
+                if sliceamount > amount {
+                    sliceamount = amount;
+                }
+                bfot_send_amount += sliceamount.checked_mul(rate(fot_amount)).unwrap();
+                fot_amount -= sliceamount;
+                amount -= sliceamount;
+            }
+            bfot_send_amount.u128()
+        });
+        std::panic::set_hook(hook);
+        result.ok()
+    }
+
+    fn check(fot_supply: u128, fot_amount: u128) {
+        let closed = calc_bfot_amount(&default_tiers(), Uint128::from(fot_supply), Uint128::from(fot_amount))
+            .ok()
+            .map(|amount| amount.u128());
+        assert_eq!(closed, reference_bfot_amount(fot_supply, fot_amount), "supply {} amount {}", fot_supply, fot_amount);
+    }
+
+    #[test]
+    fn closed_form_matches_loop() {
+        let max_supply = 112 * FOT_STEP;
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+        for _ in 0..2_000 {
+            let fot_supply = rng.below(max_supply);
+            // mostly within the supply, sometimes past it
+            let fot_amount = match rng.next() % 4 {
+                0 => rng.below(FOT_STEP),
+                1 => rng.below(max_supply),
+                _ => rng.below(fot_supply + 1),
+            };
+            check(fot_supply, fot_amount);
+        }
+
+        // tier edges
+        for tiers in [0u128, 1, 2, 109, 110, 111] {
+            for delta in [0u128, 1] {
+                let fot_supply = tiers * FOT_STEP + delta;
+                for fot_amount in [0, 1, FOT_STEP - 1, FOT_STEP, FOT_STEP + 1, fot_supply, fot_supply + 1] {
+                    check(fot_supply, fot_amount);
+                }
+            }
+        }
+    }
+
+    fn check_simulations(tiers: &[Tier], rng: &mut Rng) {
+        let top = tiers.last().unwrap().threshold.u128();
+        for _ in 0..500 {
+            let fot_supply = Uint128::from(rng.below(top) + 1);
+            let fot_amount = Uint128::from(rng.below(fot_supply.u128() + 1));
+            let bfot_out = calc_bfot_amount(tiers, fot_supply, fot_amount).unwrap();
+
+            let fills = calc_tier_fills(tiers, fot_supply, fot_amount).unwrap();
+            assert_eq!(fills.iter().map(|fill| fill.bfot_amount).sum::<Uint128>(), bfot_out);
+            assert_eq!(fills.iter().map(|fill| fill.fot_amount).sum::<Uint128>(), fot_amount);
+
+            // the least FOT paying at least bfot_out
+            let fot_needed = calc_fot_amount(tiers, fot_supply, bfot_out).unwrap();
+            assert!(fot_needed <= fot_amount);
+            assert!(calc_bfot_amount(tiers, fot_supply, fot_needed).unwrap() >= bfot_out);
+            if !fot_needed.is_zero() {
+                assert!(calc_bfot_amount(tiers, fot_supply, fot_needed - Uint128::from(1u128)).unwrap() < bfot_out);
+            }
+        }
+    }
+
+    #[test]
+    fn simulations_agree_with_closed_form() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        check_simulations(&default_tiers(), &mut rng);
+
+        // uneven widths, flat runs and zero rates at the top
+        let mut tiers = vec![];
+        let mut threshold = 0u128;
+        let mut rate = 1_000u128;
+        for _ in 0..40 {
+            threshold += rng.below(FOT_STEP) + 1;
+            rate = rate.saturating_sub(rng.below(60));
+            tiers.push(Tier { threshold: Uint128::from(threshold), rate: Uint128::from(rate) });
+        }
+        check_tiers(&tiers).unwrap();
+        check_simulations(&tiers, &mut rng);
+    }
+
+    #[test]
+    fn tiers_must_be_monotonic() {
+        let tier = |threshold: u128, rate: u128| Tier { threshold: Uint128::from(threshold), rate: Uint128::from(rate) };
+        assert!(check_tiers(&default_tiers()).is_ok());
+        assert!(check_tiers(&[]).is_err());
+        assert!(check_tiers(&[tier(0, 5)]).is_err());
+        assert!(check_tiers(&[tier(10, 5), tier(10, 4)]).is_err());
+        assert!(check_tiers(&[tier(10, 5), tier(20, 6)]).is_err());
+        assert!(check_tiers(&[tier(10, 5), tier(20, 5)]).is_ok());
+    }
+
+    #[test]
+    fn amount_before_fee_is_least() {
+        let mut rng = Rng(0x6A09_E667_F3BC_C908);
+        for _ in 0..2_000 {
+            let fee_bps = rng.below(MAX_FEE_BPS as u128 + 1) as u64;
+            let amount = Uint128::from(rng.below(1u128 << 100));
+            let gross = calc_amount_before_fee(amount, fee_bps);
+            assert!(gross - calc_fee(gross, fee_bps) >= amount);
+            if !gross.is_zero() {
+                let less = gross - Uint128::from(1u128);
+                assert!(less - calc_fee(less, fee_bps) < amount);
+            }
+        }
+    }
+
+    #[test]
+    fn referral_links_reject_self_and_loops() {
+        let mut storage = MockStorage::new();
+        let (a, b, c) = (Addr::unchecked("a"), Addr::unchecked("b"), Addr::unchecked("c"));
+        assert!(matches!(resolve_referrer(&mut storage, &a, Some(a.clone())), Err(ContractError::SelfReferral {})));
+        assert_eq!(resolve_referrer(&mut storage, &b, Some(a.clone())).unwrap(), Some(a.clone()));
+        assert_eq!(resolve_referrer(&mut storage, &c, Some(b.clone())).unwrap(), Some(b.clone()));
+        assert!(matches!(resolve_referrer(&mut storage, &a, Some(c.clone())), Err(ContractError::ReferralLoop {})));
+        //the link sticks for later burns, another referrer is ignored
+        assert_eq!(resolve_referrer(&mut storage, &c, Some(a.clone())).unwrap(), Some(b.clone()));
+        assert_eq!(resolve_referrer(&mut storage, &c, None).unwrap(), Some(b));
+        assert_eq!(REFERRALS.load(&storage, &a).unwrap().referred_count, 1);
+    }
+
+    #[test]
+    fn referral_chain_depth_is_capped() {
+        let mut storage = MockStorage::new();
+        let user = |i: u32| Addr::unchecked(format!("user{}", i));
+        for i in 1..=MAX_REFERRAL_DEPTH + 1 {
+            resolve_referrer(&mut storage, &user(i), Some(user(i - 1))).unwrap();
+        }
+        //user{MAX} has MAX uplines, one more is too many to walk
+        let referrer = resolve_referrer(&mut storage, &Addr::unchecked("last"), Some(user(MAX_REFERRAL_DEPTH))).unwrap();
+        assert_eq!(referrer, Some(user(MAX_REFERRAL_DEPTH)));
+        let err = resolve_referrer(&mut storage, &Addr::unchecked("next"), Some(user(MAX_REFERRAL_DEPTH + 1))).unwrap_err();
+        assert_eq!(err, ContractError::ReferralChainTooDeep { max_depth: MAX_REFERRAL_DEPTH });
+    }
+
+    fn mock_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            owner: None,
+            fot_token_address: "fot".to_string(),
+            bfot_token_address: "bfot".to_string(),
+            fot_start_supply: Some(Uint128::from(FOT_STEP * MAX_FOT_TIER)),
+            tiers: None,
+            min_reserve: None,
+            funder_allowlist: None,
+            limits: None,
+            fee: None,
+            guardian: Some("guardian".to_string()),
+        }
+    }
+
+    fn mock_instance_with(msg: InstantiateMsg) -> cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        deps
+    }
+
+    fn mock_instance() -> cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        mock_instance_with(mock_instantiate_msg())
+    }
+
+    // 2000 FOT left to burn, the first 1000 pay 2 bFOT each and the last 1000 pay 3
+    fn small_tiers_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            fot_start_supply: Some(Uint128::from(2000u128)),
+            tiers: Some(vec![
+                Tier { threshold: Uint128::from(1000u128), rate: Uint128::from(3u128) },
+                Tier { threshold: Uint128::from(2000u128), rate: Uint128::from(2u128) },
+            ]),
+            ..mock_instantiate_msg()
+        }
+    }
+
+    fn receive(sender: &str, amount: u128, msg: Option<ReceiveMsg>) -> ExecuteMsg {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(amount),
+            msg: msg.map_or_else(Binary::default, |msg| to_binary(&msg).unwrap()),
+        })
+    }
+
+    fn fund(deps: &mut cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier>, funder: &str, amount: u128) {
+        let msg = receive(funder, amount, Some(ReceiveMsg::Fund {}));
+        execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), msg).unwrap();
+    }
+
+    fn cw20_msg(token: &str, msg: Cw20ExecuteMsg) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            funds: vec![],
+            msg: to_binary(&msg).unwrap(),
+        })
+    }
+
+    fn transfer(token: &str, recipient: &str, amount: u128) -> CosmosMsg {
+        cw20_msg(token, Cw20ExecuteMsg::Transfer { recipient: recipient.to_string(), amount: Uint128::from(amount) })
+    }
+
+    #[test]
+    fn partial_fill_burns_what_the_pool_pays_for() {
+        let mut deps = mock_instance_with(small_tiers_msg());
+        fund(&mut deps, "funder", 2500);
+
+        //1500 FOT are worth 1000 * 2 + 500 * 3 = 3500 bFOT
+        let burn = |allow_partial| receive("burner", 1500, Some(ReceiveMsg::Fot { allow_partial }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), burn(false)).unwrap_err();
+        assert_eq!(err, ContractError::NotEnoughbFOT { bfot_accept_amount: Uint128::from(2500u128) });
+
+        //2500 bFOT pay for 1000 * 2 + 166 * 3 = 2498, the other 334 FOT go back
+        let res = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), burn(true)).unwrap();
+        let messages: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(messages, vec![
+            transfer("bfot", "burner", 2498),
+            cw20_msg("fot", Cw20ExecuteMsg::Burn { amount: Uint128::from(1166u128) }),
+            transfer("fot", "burner", 334),
+        ]);
+        assert!(res.attributes.contains(&attr("fot_refund_amount", "334")));
+
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.fot_burn_amount, Uint128::from(1166u128));
+        assert_eq!(config.bfot_sent_amount, Uint128::from(2498u128));
+        assert_eq!(config.bfot_current_amount, Uint128::from(2u128));
+        assert_eq!(config.fot_position, Uint128::from(834u128));
+    }
+
+    fn burn_msg(min_bfot_out: Option<u128>, recipient: Option<&str>, deadline: Option<Expiration>) -> ReceiveMsg {
+        ReceiveMsg::Burn {
+            min_bfot_out: min_bfot_out.map(Uint128::from),
+            recipient: recipient.map(|r| r.to_string()),
+            deadline,
+            allow_partial: false,
+            referrer: None,
+        }
+    }
+
+    #[test]
+    fn burn_checks_min_out_after_fee_and_deadline() {
+        let fee = FeeConfig { bps: 100, treasury: None, referral_bps: 0 };
+        let mut deps = mock_instance_with(InstantiateMsg { fee: Some(fee), ..small_tiers_msg() });
+        fund(&mut deps, "funder", 10_000);
+        let env = mock_env();
+
+        //100 FOT pay 200 bFOT, 198 after the 1% fee
+        let burn = receive("burner", 100, Some(burn_msg(Some(200), None, None)));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn).unwrap_err();
+        assert_eq!(err, ContractError::MinBfotOut { bfot_out: Uint128::from(198u128), min_bfot_out: Uint128::from(200u128) });
+
+        let deadline = Expiration::AtHeight(env.block.height);
+        let burn = receive("burner", 100, Some(burn_msg(Some(198), None, Some(deadline))));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn).unwrap_err();
+        assert_eq!(err, ContractError::DeadlinePassed { deadline });
+
+        let deadline = Expiration::AtHeight(env.block.height + 1);
+        let burn = receive("burner", 100, Some(burn_msg(Some(198), None, Some(deadline))));
+        let res = execute(deps.as_mut(), env, mock_info("fot", &[]), burn).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "burner", 198));
+        assert_eq!(query_config(deps.as_ref()).unwrap().fee_kept, Uint128::from(2u128));
+    }
+
+    #[test]
+    fn burn_pays_the_recipient() {
+        let mut deps = mock_instance_with(small_tiers_msg());
+        fund(&mut deps, "funder", 10_000);
+
+        let burn = receive("burner", 100, Some(burn_msg(None, Some("friend"), None)));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), burn).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "friend", 200));
+        assert!(res.attributes.contains(&attr("recipient", "friend")));
+        //the burn is still the sender's
+        assert_eq!(query_burner(deps.as_ref(), "burner".to_string()).unwrap().bfot_received, Uint128::from(200u128));
+        assert!(query_burner(deps.as_ref(), "friend".to_string()).is_err());
+    }
+
+    #[test]
+    fn pool_takes_fund_payloads_from_allowed_funders() {
+        let msg = InstantiateMsg { funder_allowlist: Some(vec!["alice".to_string()]), ..mock_instantiate_msg() };
+        let mut deps = mock_instance_with(msg);
+
+        for payload in [None, Some(ReceiveMsg::Bfot {})] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), receive("alice", 100, payload)).unwrap_err();
+            assert_eq!(err, ContractError::FundPayloadRequired {});
+        }
+        let fund_msg = |funder| receive(funder, 100, Some(ReceiveMsg::Fund {}));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), fund_msg("bob")).unwrap_err();
+        assert_eq!(err, ContractError::FunderNotAllowed { address: "bob".to_string() });
+        execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), fund_msg("alice")).unwrap();
+        assert_eq!(query_config(deps.as_ref()).unwrap().bfot_current_amount, Uint128::from(100u128));
+
+        //dropping the allowlist lets anyone fund
+        let update = ExecuteMsg::UpdateFunders { allowlist: None };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), update).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), fund_msg("bob")).unwrap();
+    }
+
+    #[test]
+    fn funders_are_paged_by_address() {
+        let mut deps = mock_instance();
+        for (funder, amount) in [("carol", 30), ("alice", 10), ("bob", 20), ("alice", 5)] {
+            fund(&mut deps, funder, amount);
+        }
+        let funder = |address: &str, amount: u128| FunderResponse { address: address.to_string(), amount: Uint128::from(amount) };
+
+        let page = query_funders(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(page.funders, vec![funder("alice", 15), funder("bob", 20)]);
+        let page = query_funders(deps.as_ref(), Some("bob".to_string()), Some(2)).unwrap();
+        assert_eq!(page.funders, vec![funder("carol", 30)]);
+        let page = query_funders(deps.as_ref(), Some("carol".to_string()), None).unwrap();
+        assert!(page.funders.is_empty());
+    }
+
+    #[test]
+    fn burns_stay_under_the_caps() {
+        let limits = BurnLimits {
+            max_per_tx: Some(Uint128::from(100u128)),
+            max_per_address: Some(Uint128::from(150u128)),
+            max_per_epoch: Some(Uint128::from(250u128)),
+            epoch_length: 3600,
+        };
+        let mut deps = mock_instance_with(InstantiateMsg { limits: Some(limits), ..small_tiers_msg() });
+        fund(&mut deps, "funder", 10_000);
+        let mut env = mock_env();
+        let burn = |burner, amount| receive(burner, amount, None);
+
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn("alice", 101)).unwrap_err();
+        assert_eq!(err, ContractError::TxLimitExceeded { max_per_tx: Uint128::from(100u128) });
+        execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn("alice", 100)).unwrap();
+
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn("alice", 60)).unwrap_err();
+        assert_eq!(err, ContractError::AddressLimitExceeded { remaining: Uint128::from(50u128) });
+        let remaining = query_limits(deps.as_ref(), env.clone(), "alice".to_string()).unwrap();
+        assert_eq!(remaining.address_remaining, Some(Uint128::from(50u128)));
+        assert_eq!(remaining.epoch_remaining, Some(Uint128::from(150u128)));
+
+        execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn("bob", 100)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), burn("carol", 60)).unwrap_err();
+        assert_eq!(err, ContractError::EpochLimitExceeded { remaining: Uint128::from(50u128) });
+        let remaining = query_limits(deps.as_ref(), env.clone(), "carol".to_string()).unwrap();
+        assert_eq!(remaining.address_remaining, Some(Uint128::from(150u128)));
+        assert_eq!(remaining.epoch_remaining, Some(Uint128::from(50u128)));
+
+        //a day later both the address window and the epoch are fresh
+        env.block.time = env.block.time.plus_seconds(86400);
+        let remaining = query_limits(deps.as_ref(), env.clone(), "alice".to_string()).unwrap();
+        assert_eq!(remaining.address_remaining, Some(Uint128::from(150u128)));
+        assert_eq!(remaining.epoch_remaining, Some(Uint128::from(250u128)));
+        execute(deps.as_mut(), env, mock_info("fot", &[]), burn("alice", 100)).unwrap();
+    }
+
+    #[test]
+    fn withdrawals_keep_the_reserve_while_swaps_run() {
+        let msg = InstantiateMsg { min_reserve: Some(Uint128::from(400u128)), ..small_tiers_msg() };
+        let mut deps = mock_instance_with(msg);
+        fund(&mut deps, "funder", 1000);
+        let withdraw = |amount: u128, recipient: Option<&str>| ExecuteMsg::Withdraw {
+            amount: Uint128::from(amount),
+            recipient: recipient.map(|r| r.to_string()),
+        };
+        let bfot_current_amount = |deps: &cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier>|
+            query_config(deps.as_ref()).unwrap().bfot_current_amount.u128();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), withdraw(100, None)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), withdraw(601, None)).unwrap_err();
+        assert_eq!(err, ContractError::BelowReserve { available: Uint128::from(600u128) });
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), withdraw(100, None)).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "owner", 100));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), withdraw(200, Some("treasury"))).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "treasury", 200));
+        assert!(res.attributes.contains(&attr("recipient", "treasury")));
+        assert_eq!(bfot_current_amount(&deps), 700);
+
+        //all of it above the reserve, then nothing is left to take
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::WithdrawAll {}).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "owner", 300));
+        assert_eq!(bfot_current_amount(&deps), 400);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::WithdrawAll {}).unwrap_err();
+        assert_eq!(err, ContractError::BelowReserve { available: Uint128::zero() });
+
+        //the reserve still pays swaps, 100 FOT for 200 bFOT
+        execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), receive("burner", 100, None)).unwrap();
+        assert_eq!(bfot_current_amount(&deps), 200);
+
+        //with swaps off the pool drains fully
+        let update = ExecuteMsg::UpdatePool { min_reserve: None, swaps_enabled: Some(false) };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), update).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::WithdrawAll {}).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "owner", 200));
+        assert_eq!(bfot_current_amount(&deps), 0);
+    }
+
+    #[test]
+    fn burners_rank_by_fot_burned_and_keep_history() {
+        let mut deps = mock_instance_with(small_tiers_msg());
+        fund(&mut deps, "funder", 10_000);
+        let mut env = mock_env();
+        let start = env.block.time.seconds();
+        for (burner, amount) in [("alice", 100), ("bob", 400), ("carol", 200), ("dave", 100)] {
+            execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), receive(burner, amount, None)).unwrap();
+        }
+        //from position 1200, 200 FOT at 2 and 100 at 3, ending in the first tier
+        env.block.time = env.block.time.plus_seconds(60);
+        execute(deps.as_mut(), env, mock_info("fot", &[]), receive("alice", 300, None)).unwrap();
+
+        let history = query_burn_history(deps.as_ref(), "alice".to_string(), None, None).unwrap();
+        assert_eq!(history.burns, vec![
+            BurnRecordResponse {
+                id: 1,
+                fot_amount: Uint128::from(100u128),
+                bfot_amount: Uint128::from(200u128),
+                start_tier: 2,
+                end_tier: 2,
+                time: start
+            },
+            BurnRecordResponse {
+                id: 2,
+                fot_amount: Uint128::from(300u128),
+                bfot_amount: Uint128::from(700u128),
+                start_tier: 2,
+                end_tier: 1,
+                time: start + 60
+            },
+        ]);
+        let history = query_burn_history(deps.as_ref(), "alice".to_string(), Some(1), None).unwrap();
+        assert_eq!(history.burns.iter().map(|b| b.id).collect::<Vec<_>>(), vec![2]);
+
+        let alice = query_burner(deps.as_ref(), "alice".to_string()).unwrap();
+        assert_eq!((alice.fot_burned.u128(), alice.bfot_received.u128(), alice.burn_count), (400, 900, 2));
+        assert_eq!((alice.first_burn, alice.last_burn), (start, start + 60));
+
+        //alice and bob tie at 400, equal amounts come in descending address order
+        let top = |start_after: Option<&str>, limit| query_top_burners(deps.as_ref(), start_after.map(|a| a.to_string()), limit)
+            .unwrap()
+            .burners
+            .into_iter()
+            .map(|b| (b.address, b.fot_burned.u128()))
+            .collect::<Vec<_>>();
+        let ranked = |list: &[(&str, u128)]| list.iter().map(|(a, n)| (a.to_string(), *n)).collect::<Vec<_>>();
+        assert_eq!(top(None, None), ranked(&[("bob", 400), ("alice", 400), ("carol", 200), ("dave", 100)]));
+        assert_eq!(top(None, Some(1)), ranked(&[("bob", 400)]));
+        assert_eq!(top(Some("bob"), Some(2)), ranked(&[("alice", 400), ("carol", 200)]));
+        assert_eq!(top(Some("alice"), None), ranked(&[("carol", 200), ("dave", 100)]));
+        assert_eq!(top(Some("dave"), None), ranked(&[]));
+    }
+
+    fn mock_fot_supply(deps: &mut cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier>, supply: u128) {
+        deps.querier.update_wasm(move |_| {
+            let info = TokenInfoResponse {
+                name: "FOT".to_string(),
+                symbol: "FOT".to_string(),
+                decimals: 10,
+                total_supply: Uint128::from(supply),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_binary(&info).unwrap()))
+        });
+    }
+
+    // a deployment from before the tiers, priced off the live FOT supply
+    fn mock_baseline_instance(fot_burn_amount: u128, fot_supply: u128) -> cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.0").unwrap();
+        let config = format!(
+            r#"{{"owner":"owner","fot_token_address":"fot","bfot_token_address":"bfot","fot_burn_amount":"{}","bfot_sent_amount":"0","bfot_current_amount":"100000"}}"#,
+            fot_burn_amount
+        );
+        deps.as_mut().storage.set(b"config", config.as_bytes());
+        mock_fot_supply(&mut deps, fot_supply);
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        deps
+    }
+
+    #[test]
+    fn migrate_baseline_config() {
+        //three tiers burned, 5 FOT into the tier paying 59
+        let mut deps = mock_baseline_instance(3 * FOT_STEP, 50 * FOT_STEP + 5);
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.fot_start_supply, Uint128::from(53 * FOT_STEP + 5));
+        assert_eq!(config.fot_position, Uint128::from(50 * FOT_STEP + 5));
+        assert_eq!(config.bfot_current_amount, Uint128::from(100_000u128));
+        assert_eq!(query_tiers(deps.as_ref(), mock_env()).unwrap().tiers, default_tiers());
+
+        //the same price as before, 5 FOT at 59 and 1 at 60, however the live supply moves on
+        mock_fot_supply(&mut deps, 0);
+        let simulation = query_simulate(deps.as_ref(), mock_env(), Uint128::from(6u128)).unwrap();
+        assert_eq!(simulation.bfot_out, Uint128::from(5 * 59 + 60u128));
+
+        //nothing left to burn, and more than is left
+        let deps = &mut mock_baseline_instance(3 * FOT_STEP, 0);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), receive("burner", 1, None)).unwrap_err();
+        assert_eq!(err, ContractError::NotEnoughFOT {});
+        let deps = &mut mock_baseline_instance(3 * FOT_STEP, 5);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), receive("burner", 6, None)).unwrap_err();
+        assert_eq!(err, ContractError::NotEnoughFOT {});
+
+        //a supply past the last tier has no rate
+        let deps = &mut mock_baseline_instance(0, MAX_FOT_TIER * FOT_STEP + 1);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), receive("burner", 1, None)).unwrap_err();
+        assert_eq!(err, ContractError::FotTierOutOfRange { tier: MAX_FOT_TIER + 1 });
+    }
+
+    #[test]
+    fn tier_updates_take_over_at_their_height() {
+        let mut deps = mock_instance_with(small_tiers_msg());
+        fund(&mut deps, "funder", 10_000);
+        let mut env = mock_env();
+        let old_tiers = small_tiers_msg().tiers.unwrap();
+        let new_tiers = vec![
+            Tier { threshold: Uint128::from(1000u128), rate: Uint128::from(6u128) },
+            Tier { threshold: Uint128::from(2000u128), rate: Uint128::from(4u128) },
+        ];
+        let update = |effective_height| ExecuteMsg::UpdateTiers { tiers: new_tiers.clone(), effective_height };
+
+        for effective_height in [env.block.height, env.block.height - 1] {
+            let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), update(effective_height)).unwrap_err();
+            assert_eq!(err, ContractError::TiersNotInFuture { effective_height });
+        }
+        let effective_height = env.block.height + 10;
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), update(effective_height)).unwrap();
+        let tiers = query_tiers(deps.as_ref(), env.clone()).unwrap();
+        assert_eq!(tiers.tiers, old_tiers);
+        assert_eq!(tiers.pending, Some(PendingTiers { tiers: new_tiers.clone(), effective_height }));
+
+        //100 FOT pay 2 each until the height, then 4
+        env.block.height = effective_height - 1;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), receive("burner", 100, None)).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "burner", 200));
+        env.block.height = effective_height;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("fot", &[]), receive("burner", 100, None)).unwrap();
+        assert_eq!(res.messages[0].msg, transfer("bfot", "burner", 400));
+        let tiers = query_tiers(deps.as_ref(), env).unwrap();
+        assert_eq!(tiers.tiers, new_tiers);
+        assert_eq!(tiers.pending, None);
+    }
+
+    #[test]
+    fn referrer_earns_a_share_of_every_fee() {
+        let fee = FeeConfig { bps: 1000, treasury: Some("treasury".to_string()), referral_bps: 5000 };
+        let mut deps = mock_instance_with(InstantiateMsg { fee: Some(fee), ..small_tiers_msg() });
+        fund(&mut deps, "funder", 10_000);
+        let burn = |referrer: Option<&str>| receive("burner", 100, Some(ReceiveMsg::Burn {
+            min_bfot_out: None,
+            recipient: None,
+            deadline: None,
+            allow_partial: false,
+            referrer: referrer.map(|r| r.to_string()),
+        }));
+
+        //200 bFOT, a 20 fee split between the referrer and the treasury
+        let res = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), burn(Some("referrer"))).unwrap();
+        let messages: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(messages, vec![
+            transfer("bfot", "burner", 180),
+            transfer("bfot", "referrer", 10),
+            transfer("bfot", "treasury", 10),
+            cw20_msg("fot", Cw20ExecuteMsg::Burn { amount: Uint128::from(100u128) }),
+        ]);
+        assert!(res.attributes.contains(&attr("referral_amount", "10")));
+
+        //later burns pay the same referrer, whoever is passed
+        let res = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), burn(Some("other"))).unwrap();
+        assert_eq!(res.messages[1].msg, transfer("bfot", "referrer", 10));
+        let referrals = query_referrals(deps.as_ref(), "referrer".to_string()).unwrap();
+        assert_eq!((referrals.referred_count, referrals.reward_amount.u128()), (1, 20));
+        assert_eq!(query_referrals(deps.as_ref(), "other".to_string()).unwrap().reward_amount, Uint128::zero());
+    }
+
+    #[test]
+    fn guardian_pauses_swaps() {
+        let mut deps = mock_instance();
+
+        let pause = ExecuteMsg::Pause { flags: PauseFlags { swaps: true } };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), pause.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause).unwrap();
+        assert!(query_status(deps.as_ref()).unwrap().paused.swaps);
+
+        let burn = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "burner".to_string(),
+            amount: Uint128::from(1u128),
+            msg: Binary::default(),
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("fot", &[]), burn).unwrap_err();
+        assert_eq!(err, ContractError::Paused { action: "swaps".to_string() });
+
+        //the owner can resume as well
+        let unpause = ExecuteMsg::Unpause { flags: PauseFlags { swaps: true } };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), unpause).unwrap();
+        assert!(!query_status(deps.as_ref()).unwrap().paused.swaps);
+    }
+
+    #[test]
+    fn ownership_moves_on_accept() {
+        let mut deps = mock_instance();
+        let mut env = mock_env();
+        let propose = ExecuteMsg::ProposeOwner {
+            address: "next".to_string(),
+            expiry: Some(Expiration::AtHeight(env.block.height + 10)),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), propose).unwrap();
+        assert_eq!(query_config(deps.as_ref()).unwrap().pending_owner.unwrap().owner, Addr::unchecked("next"));
+
+        let err = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), ExecuteMsg::AcceptOwnership {}).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        env.block.height += 10;
+        let err = execute(deps.as_mut(), env.clone(), mock_info("next", &[]), ExecuteMsg::AcceptOwnership {}).unwrap_err();
+        assert!(matches!(err, ContractError::OwnerProposalExpired { .. }));
+
+        let propose = ExecuteMsg::ProposeOwner { address: "next".to_string(), expiry: None };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), propose).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("next", &[]), ExecuteMsg::AcceptOwnership {}).unwrap();
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.owner, Some("next".to_string()));
+        assert_eq!(config.pending_owner, None);
+
+        execute(deps.as_mut(), env.clone(), mock_info("next", &[]), ExecuteMsg::RenounceOwnership {}).unwrap();
+        assert_eq!(query_config(deps.as_ref()).unwrap().owner, None);
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use cw_utils::{Expiration, Scheduled};
 use hex::FromHexError;
 use thiserror::Error;
@@ -8,12 +8,21 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("{0}")]
     Hex(#[from] FromHexError),
 
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("No pending ownership proposal")]
+    NoOwnerProposal {},
+
+    #[error("Ownership proposal expired at {expiry}")]
+    OwnerProposalExpired { expiry: Expiration },
+
     #[error("InvalidInput")]
     InvalidInput {},
 
@@ -23,8 +32,68 @@ pub enum ContractError {
     #[error("Not enough FOT")]
     NotEnoughFOT {},
 
-    #[error("Not enough bFOT")]
-    NotEnoughbFOT {},
+    #[error("FOT tier {tier} is past the last tier")]
+    FotTierOutOfRange { tier: u128 },
+
+    #[error("bFOT deposits must be sent with the fund payload")]
+    FundPayloadRequired {},
+
+    #[error("{address} is not allowed to fund the pool")]
+    FunderNotAllowed { address: String },
+
+    #[error("Burn is over the limit of {max_per_tx} FOT per transaction")]
+    TxLimitExceeded { max_per_tx: Uint128 },
+
+    #[error("Burn is over the address limit, {remaining} FOT left in the 24h window")]
+    AddressLimitExceeded { remaining: Uint128 },
+
+    #[error("Burn is over the epoch limit, {remaining} FOT left this epoch")]
+    EpochLimitExceeded { remaining: Uint128 },
+
+    #[error("Invalid burn limits")]
+    InvalidLimits {},
+
+    #[error("Fee is over the maximum of {max_bps} bps")]
+    FeeTooHigh { max_bps: u64 },
+
+    #[error("No fees to withdraw")]
+    NoFees {},
+
+    #[error("Referral share is over the maximum of {max_bps} bps")]
+    ReferralTooHigh { max_bps: u64 },
+
+    #[error("Cannot refer yourself")]
+    SelfReferral {},
+
+    #[error("Referral loop")]
+    ReferralLoop {},
+
+    #[error("Referral chain is longer than {max_depth}")]
+    ReferralChainTooDeep { max_depth: u32 },
+
+    #[error("Swaps are disabled")]
+    SwapsDisabled {},
+
+    #[error("{action} paused by the guardian")]
+    Paused { action: String },
+
+    #[error("Withdrawal would leave the pool below its minimum reserve, {available} available")]
+    BelowReserve { available: Uint128 },
+
+    #[error("Invalid tier table")]
+    InvalidTiers {},
+
+    #[error("Tier table must take effect after the current height, not {effective_height}")]
+    TiersNotInFuture { effective_height: u64 },
+
+    #[error("Not enough bFOT, {bfot_accept_amount} available")]
+    NotEnoughbFOT { bfot_accept_amount: Uint128 },
+
+    #[error("bFOT out {bfot_out} is below the minimum {min_bfot_out}")]
+    MinBfotOut { bfot_out: Uint128, min_bfot_out: Uint128 },
+
+    #[error("Burn deadline {deadline} passed")]
+    DeadlinePassed { deadline: Expiration },
 
     #[error("Already claimed")]
     Claimed {},
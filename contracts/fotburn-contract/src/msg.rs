@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cw20::{Cw20ReceiveMsg};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Addr, Uint128};
+use cw_utils::Expiration;
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InstantiateMsg {
@@ -9,30 +10,180 @@ pub struct InstantiateMsg {
     pub owner: Option<String>,
     pub fot_token_address: String,
     pub bfot_token_address: String,
+    /// FOT position to start pricing the tiers from, the FOT total supply if not set.
+    pub fot_start_supply: Option<Uint128>,
+    /// Tier table, the original 110 tiers if not set.
+    pub tiers: Option<Vec<Tier>>,
+    /// bFOT the owner can't withdraw while swaps are enabled, zero if not set.
+    pub min_reserve: Option<Uint128>,
+    /// Addresses allowed to fund the bFOT pool, anyone if not set.
+    pub funder_allowlist: Option<Vec<String>>,
+    /// No caps if not set.
+    pub limits: Option<BurnLimits>,
+    /// No fee if not set.
+    pub fee: Option<FeeConfig>,
+    /// Can pause and unpause besides the owner, none if not set.
+    pub guardian: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeConfig {
+    /// Share of the bFOT paid out taken as fee, in basis points
+    pub bps: u64,
+    /// Where the fee goes. If not set it's kept here until the owner sends it out with
+    /// WithdrawFees, nothing passes it on to stakers.
+    pub treasury: Option<String>,
+    /// Share of the fee paid to the burner's referrer, in basis points of the fee
+    #[serde(default)]
+    pub referral_bps: u64
+}
+
+/// Caps on the FOT burned, a cap not set doesn't apply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BurnLimits {
+    pub max_per_tx: Option<Uint128>,
+    /// Per address within any 24h
+    pub max_per_address: Option<Uint128>,
+    /// Across all addresses within an epoch
+    pub max_per_epoch: Option<Uint128>,
+    /// Epoch length in seconds, epochs count from the unix epoch
+    pub epoch_length: u64
+}
+
+/// FOT positions up to threshold, above the previous tier's, pay rate bFOT per FOT.
+/// Thresholds rise through the table and rates don't.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Tier {
+    pub threshold: Uint128,
+    pub rate: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTiers {
+    pub tiers: Vec<Tier>,
+    /// Block height the table replaces the current one at
+    pub effective_height: u64
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    UpdateConfig {
-        /// NewOwner if non sent, contract gets locked. Recipients can receive airdrops
-        /// but owner cannot register new stages.
-        new_owner: Option<String>,
+    /// Offers ownership to the address, it takes over once it accepts
+    ProposeOwner {
+        address: String,
+        /// Proposal never expires if not set
+        expiry: Option<Expiration>,
     },
+    AcceptOwnership {},
+    /// Drops the pending proposal
+    CancelProposal {},
+    /// Leaves the contract without an owner for good
+    RenounceOwnership {},
     Receive(Cw20ReceiveMsg),
+    /// Withdraw all the bFOT above the minimum reserve, or all of it once swaps are disabled
     WithdrawAll { },
+    Withdraw {
+        amount: Uint128,
+        /// Owner if not set
+        recipient: Option<String>,
+    },
+    UpdateLimits {
+        limits: BurnLimits,
+    },
+    UpdateFee {
+        fee: FeeConfig,
+    },
+    /// Owner only, sends the kept fees to the recipient, the owner if not set
+    WithdrawFees {
+        recipient: Option<String>,
+    },
+    /// Allowlist of bFOT pool funders, anyone can fund if not set
+    UpdateFunders {
+        allowlist: Option<Vec<String>>,
+    },
+    /// Fields not set are left unchanged
+    UpdatePool {
+        min_reserve: Option<Uint128>,
+        swaps_enabled: Option<bool>,
+    },
+    /// Replace the tier table from a future block height on
+    UpdateTiers {
+        tiers: Vec<Tier>,
+        effective_height: u64
+    },
+    /// Owner only, removes the guardian if not set
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Halts the flagged actions, guardian or owner only
+    Pause {
+        flags: PauseFlags,
+    },
+    /// Resumes the flagged actions, guardian or owner only
+    Unpause {
+        flags: PauseFlags,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveMsg {
-    Fot {},
+    Fot {
+        /// If the bFOT pool can't pay for all the FOT, burn what it can pay for and
+        /// send the rest of the FOT back instead of failing.
+        #[serde(default)]
+        allow_partial: bool
+    },
     Bfot {},
+    /// Deposit the sent bFOT into the pool
+    Fund {},
+    /// Burn the sent FOT with a slippage guard
+    Burn {
+        /// Fail if the tier moved and less bFOT would be paid
+        min_bfot_out: Option<Uint128>,
+        /// bFOT goes here instead of the sender. Unpaid FOT still goes back to the sender.
+        recipient: Option<String>,
+        /// Fail if the burn lands after this
+        deadline: Option<Expiration>,
+        #[serde(default)]
+        allow_partial: bool,
+        /// Set once per burner, later burns pay the same referrer and ignore this one
+        referrer: Option<String>
+    },
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Guardian and the paused actions
+    Status {},
+    /// bFOT paid for burning fot_amount FOT now, tier by tier
+    Simulate { fot_amount: Uint128 },
+    /// FOT to burn now to receive at least bfot_amount bFOT
+    ReverseSimulate { bfot_amount: Uint128 },
+    Tiers {},
+    /// bFOT contributed to the pool per funder
+    Funders {
+        start_after: Option<String>,
+        limit: Option<u32>
+    },
+    /// Burn totals of an address
+    Burner { address: String },
+    /// Caps and what the address can still burn under them
+    Limits { address: String },
+    /// Burns of an address, oldest first. start_after is a burn id.
+    BurnHistory {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>
+    },
+    /// Burners by FOT burned, most first
+    TopBurners {
+        start_after: Option<String>,
+        limit: Option<u32>
+    },
+    /// Burners referred and bFOT earned by a referrer
+    Referrals { referrer: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -43,10 +194,150 @@ pub struct ConfigResponse {
     pub bfot_token_address: String,
     pub fot_burn_amount: Uint128,
     pub bfot_sent_amount: Uint128,
-    pub bfot_current_amount: Uint128
+    pub bfot_current_amount: Uint128,
+    pub fot_start_supply: Uint128,
+    /// Position the next burn is priced from
+    pub fot_position: Uint128,
+    pub min_reserve: Uint128,
+    pub swaps_enabled: bool,
+    pub funder_allowlist: Option<Vec<String>>,
+    pub fee_bps: u64,
+    pub fee_treasury: Option<String>,
+    /// Fees kept here, not yet withdrawn
+    pub fee_kept: Uint128,
+    pub fee_referral_bps: u64,
+    pub pending_owner: Option<OwnerProposal>
+
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct TierFill {
+    pub tier: u128,
+    /// bFOT per FOT in this tier
+    pub rate: Uint128,
+    pub fot_amount: Uint128,
+    pub bfot_amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct SimulateResponse {
+    /// bFOT received after the fee
+    pub bfot_out: Uint128,
+    pub fee_amount: Uint128,
+    /// Tiers crossed, from the current one down
+    pub tiers: Vec<TierFill>,
+    /// Whether bfot_current_amount covers bfot_out
+    pub pool_sufficient: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ReverseSimulateResponse {
+    pub fot_amount: Uint128,
+    /// bFOT actually received for fot_amount after the fee, at least the asked amount
+    pub bfot_out: Uint128,
+    pub fee_amount: Uint128,
+    pub pool_sufficient: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct TiersResponse {
+    /// Table in effect at the current height
+    pub tiers: Vec<Tier>,
+    pub pending: Option<PendingTiers>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct FunderResponse {
+    pub address: String,
+    pub amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct FundersResponse {
+    pub funders: Vec<FunderResponse>
+}
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BurnerResponse {
+    pub address: String,
+    pub fot_burned: Uint128,
+    pub bfot_received: Uint128,
+    pub burn_count: u64,
+    pub first_burn: u64,
+    pub last_burn: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BurnersResponse {
+    pub burners: Vec<BurnerResponse>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BurnRecordResponse {
+    /// Counts the address's burns from 1
+    pub id: u64,
+    pub fot_amount: Uint128,
+    pub bfot_amount: Uint128,
+    pub start_tier: u128,
+    pub end_tier: u128,
+    pub time: u64
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BurnHistoryResponse {
+    pub address: String,
+    pub burns: Vec<BurnRecordResponse>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct LimitsResponse {
+    pub limits: BurnLimits,
+    /// Left for the address in the current 24h window
+    pub address_remaining: Option<Uint128>,
+    /// Left for everyone in the current epoch
+    pub epoch_remaining: Option<Uint128>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ReferralsResponse {
+    pub referrer: String,
+    pub referred_count: u64,
+    pub reward_amount: Uint128
+}
+
+/// Actions the guardian can halt. Flags not set are left alone by Pause and Unpause.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseFlags {
+    /// FOT burns for bFOT
+    #[serde(default)]
+    pub swaps: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct StatusResponse {
+    pub guardian: Option<String>,
+    pub paused: PauseFlags
+}
+
+/// Owner proposed by the current one, waiting to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerProposal {
+    pub owner: Addr,
+    pub expiry: Expiration
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -2,7 +2,9 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+
+use crate::msg::{BurnLimits, OwnerProposal, PauseFlags, PendingTiers, Tier};
 
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -13,8 +15,142 @@ pub struct Config {
     pub bfot_token_address: Addr,
     pub fot_burn_amount: Uint128,
     pub bfot_sent_amount: Uint128,
-    pub bfot_current_amount: Uint128
+    pub bfot_current_amount: Uint128,
+    /// FOT position the tiers are priced from before any burn here. The current
+    /// position is this less fot_burn_amount.
+    pub fot_start_supply: Uint128,
+    pub tiers: Vec<Tier>,
+    /// Table taking over at its effective height, applied on the next burn
+    pub pending_tiers: Option<PendingTiers>,
+    /// bFOT kept in the pool while swaps are enabled
+    pub min_reserve: Uint128,
+    pub swaps_enabled: bool,
+    /// Addresses allowed to fund the pool, anyone if None
+    pub funder_allowlist: Option<Vec<Addr>>,
+    pub limits: BurnLimits,
+    /// Fee on the bFOT paid out, in basis points
+    pub fee_bps: u64,
+    /// Fee recipient, fees are kept in fee_kept if None
+    pub fee_treasury: Option<Addr>,
+    /// bFOT fees held here outside the pool until the owner withdraws them
+    pub fee_kept: Uint128,
+    /// Share of the fee paid to referrers, in basis points of the fee
+    pub fee_referral_bps: u64
 }
 
 pub const CONFIG_KEY: &str = "config";
 pub const CONFIG: Item<Config> = Item::new(CONFIG_KEY);
+
+/// Config as stored by any earlier version, read by migrate. Fields added later are optional.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV0 {
+    pub owner: Option<Addr>,
+    pub fot_token_address: Addr,
+    pub bfot_token_address: Addr,
+    pub fot_burn_amount: Uint128,
+    pub bfot_sent_amount: Uint128,
+    pub bfot_current_amount: Uint128,
+    pub fot_start_supply: Option<Uint128>,
+    pub tiers: Option<Vec<Tier>>,
+    pub pending_tiers: Option<PendingTiers>,
+    pub min_reserve: Option<Uint128>,
+    pub swaps_enabled: Option<bool>,
+    pub funder_allowlist: Option<Vec<Addr>>,
+    pub limits: Option<BurnLimits>,
+    pub fee_bps: Option<u64>,
+    pub fee_treasury: Option<Addr>,
+    pub fee_kept: Option<Uint128>,
+    pub fee_referral_bps: Option<u64>
+}
+
+pub const CONFIG_V0: Item<ConfigV0> = Item::new(CONFIG_KEY);
+
+pub const FUNDERS_KEY: &str = "funders";
+pub const FUNDERS: Map<&Addr, Uint128> = Map::new(FUNDERS_KEY);
+
+pub const OWNER_PROPOSAL_KEY: &str = "owner_proposal";
+pub const OWNER_PROPOSAL: Item<OwnerProposal> = Item::new(OWNER_PROPOSAL_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseState {
+    /// Can pause and unpause besides the owner
+    pub guardian: Option<Addr>,
+    pub paused: PauseFlags
+}
+
+pub const PAUSE_KEY: &str = "pause";
+/// Missing on deployments from before pausing, read as nothing paused
+pub const PAUSE: Item<PauseState> = Item::new(PAUSE_KEY);
+
+/// Per-address totals of the FOT burned here
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BurnerInfo {
+    pub fot_burned: Uint128,
+    pub bfot_received: Uint128,
+    pub burn_count: u64,
+    /// Block time of the first and latest burn, in seconds
+    pub first_burn: u64,
+    pub last_burn: u64
+}
+
+pub struct BurnerIndexes<'a> {
+    // big endian fot_burned, so the index iterates in burned order
+    pub fot_burned: MultiIndex<'a, Vec<u8>, BurnerInfo, Addr>,
+}
+
+impl<'a> IndexList<BurnerInfo> for BurnerIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<BurnerInfo>> + '_> {
+        let v: Vec<&dyn Index<BurnerInfo>> = vec![&self.fot_burned];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const BURNERS_KEY: &str = "burners";
+
+pub fn burners<'a>() -> IndexedMap<'a, &'a Addr, BurnerInfo, BurnerIndexes<'a>> {
+    let indexes = BurnerIndexes {
+        fot_burned: MultiIndex::new(
+            |b: &BurnerInfo| b.fot_burned.u128().to_be_bytes().to_vec(),
+            BURNERS_KEY,
+            "burners__fot_burned",
+        ),
+    };
+    IndexedMap::new(BURNERS_KEY, indexes)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BurnRecord {
+    pub fot_amount: Uint128,
+    pub bfot_amount: Uint128,
+    /// Tiers of the first and last FOT burned
+    pub start_tier: u128,
+    pub end_tier: u128,
+    pub time: u64
+}
+
+pub const BURNS_KEY: &str = "burns";
+/// Burns per address, keyed by the address's burn count at the time
+pub const BURNS: Map<(&Addr, u64), BurnRecord> = Map::new(BURNS_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochBurned {
+    pub epoch: u64,
+    pub burned: Uint128
+}
+
+pub const EPOCH_BURNED_KEY: &str = "epoch_burned";
+pub const EPOCH_BURNED: Item<EpochBurned> = Item::new(EPOCH_BURNED_KEY);
+
+pub const REFERRERS_KEY: &str = "referrers";
+/// Referrer of each referred burner, set on their first referred burn
+pub const REFERRERS: Map<&Addr, Addr> = Map::new(REFERRERS_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReferralInfo {
+    pub referred_count: u64,
+    /// bFOT paid to the referrer out of fees
+    pub reward_amount: Uint128
+}
+
+pub const REFERRALS_KEY: &str = "referrals";
+pub const REFERRALS: Map<&Addr, ReferralInfo> = Map::new(REFERRALS_KEY);
@@ -1,79 +1,292 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
-    WasmMsg, WasmQuery, QueryRequest, CosmosMsg, SubMsg, Addr
+    attr, to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, Uint128,
+    Decimal256, WasmMsg, CosmosMsg, SubMsg, Addr, Reply, Order, Storage
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ReceiveMsg};
-use cw20::{TokenInfoResponse};
+use cw20::{MinterResponse};
+use cw_storage_plus::Bound;
+use cw_utils::{parse_reply_instantiate_data, Expiration, Scheduled};
+use sha2::Digest;
 
 use crate::error::ContractError;
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ExpectedAmountResponse, 
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ExpectedAmountResponse, GfotTokenInfo,
+    Cw20MinterMsg, CurvePoint, CurvePointsResponse, AirdropKind, LatestStageResponse, StageResponse,
+    IsClaimedResponse, VestingParams, VestingSchedule, VestingResponse, ReceiveMsg, StakingReceiveMsg,
+    CurveParams, PendingCurveParams, CurveShape, MinterOrderBy, MinterInfoResponse, MintersResponse,
+    FeeConfig, ReferralsResponse, PauseFlags, StatusResponse, OwnerProposal,
 };
+use crate::curve::{build_curve, Curve, MIN_EXPONENTIAL_GROWTH, MAX_EXPONENTIAL_GROWTH, MAX_EXPONENTIAL_STEPS};
 use crate::state::{
-    Config, CONFIG
+    Config, CONFIG, Stage, LATEST_STAGE, STAGES, CLAIMS, VESTING, CONFIG_V0, PENDING_CURVE, MinterInfo,
+    minters, REFERRERS, REFERRALS, PauseState, PAUSE, OWNER_PROPOSAL
 };
 
 use cw20_base::{
-    msg::ExecuteMsg as Cw20ExecuteMsg, msg::InstantiateMsg as Cw20InstantiateMsg, 
-    msg::QueryMsg as Cw20QueryMsg
+    msg::ExecuteMsg as Cw20ExecuteMsg, msg::InstantiateMsg as Cw20InstantiateMsg
 };
 
-use integer_sqrt::IntegerSquareRoot;
-
 // Version info, for migration info
 const CONTRACT_NAME: &str = "bfotburn";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// default curve, also used for deployments migrated from before the params were stored
 const BFOT_START_AMOUNT:u128 = 100_000_000_000_000u128;
 const STEP_AMOUNT:u128 = 10_000_000_000u128;
+
+// delay between proposing and applying new curve params
+const CURVE_TIMELOCK:u64 = 7 * 86400u64;
+
+const INSTANTIATE_GFOT_REPLY_ID:u64 = 1;
+
+const MAX_CURVE_POINTS:u32 = 200;
+
+// uplines walked to rule out a referral loop, longer chains are refused.
+// resolve_referrer is the same in fotburn and bfotburn, change both together
+const MAX_REFERRAL_DEPTH:u32 = 32;
+
+// fee on the minted gFOT, in basis points
+const BPS_DENOMINATOR:u128 = 10_000u128;
+const MAX_FEE_BPS:u64 = 1_000u64;
+
+// pagination of the minters query
+const DEFAULT_LIMIT:u32 = 10;
+const MAX_LIMIT:u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let owner = msg
         .owner
         .map_or(Ok(info.sender), |o| deps.api.addr_validate(&o))?;
 
-    let config = Config {
+    if let Some(vesting) = &msg.vesting {
+        check_vesting(vesting)?;
+    }
+
+    let curve = msg.curve.unwrap_or_else(default_curve);
+    check_curve(&curve)?;
+
+    let mut messages:Vec<SubMsg> = vec![];
+    let gfot_token_address = match msg.gfot_token {
+        GfotTokenInfo::Existing { address } => Some(deps.api.addr_validate(&address)?),
+        GfotTokenInfo::New { code_id, name, symbol, decimals, label } => {
+            messages.push(SubMsg::reply_on_success(
+                WasmMsg::Instantiate {
+                    admin: Some(owner.to_string()),
+                    code_id,
+                    msg: to_binary(&Cw20InstantiateMsg {
+                        name,
+                        symbol,
+                        decimals,
+                        initial_balances: vec![],
+                        mint: Some(MinterResponse {
+                            minter: env.contract.address.to_string(),
+                            cap: None,
+                        }),
+                        marketing: None,
+                    })?,
+                    funds: vec![],
+                    label,
+                },
+                INSTANTIATE_GFOT_REPLY_ID,
+            ));
+            None
+        }
+    };
+
+    let mut config = Config {
         owner: Some(owner),
-        bfot_token_address: msg.bfot_token_address.clone(),
-        gfot_token_address: msg.bfot_token_address.clone(),
+        bfot_token_address: msg.bfot_token_address,
+        gfot_token_address,
         bfot_burn_amount: Uint128::zero(),
         gfot_sent_amount: Uint128::zero(),
-        rate: Uint128::from(BFOT_START_AMOUNT),
-        left: Uint128::from(BFOT_START_AMOUNT)
+        rate: Uint128::zero(),
+        left: Uint128::zero(),
+        supply: Uint128::zero(),
+        successor: None,
+        vesting: msg.vesting,
+        curve,
+        fee_bps: 0,
+        fee_treasury: None,
+        fee_kept: Uint128::zero(),
+        fee_referral_bps: 0
     };
+    if let Some(fee) = msg.fee {
+        check_fee(&fee)?;
+        config.fee_bps = fee.bps;
+        config.fee_referral_bps = fee.referral_bps;
+        config.fee_treasury = fee.treasury.map(|treasury| deps.api.addr_validate(&treasury)).transpose()?;
+    }
+    let curve = build_curve(&config.curve);
+    update_curve_position(&mut config, curve.as_ref())?;
     CONFIG.save(deps.storage, &config)?;
+    let guardian = msg.guardian.map(|g| deps.api.addr_validate(&g)).transpose()?;
+    PAUSE.save(deps.storage, &PauseState { guardian, paused: PauseFlags::default() })?;
     //testnet 397 mainnet 9
     
 
-    Ok(Response::default())
+    Ok(Response::new().add_submessages(messages))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_GFOT_REPLY_ID => {
+            let res = parse_reply_instantiate_data(msg)?;
+            let gfot_token_address = deps.api.addr_validate(&res.contract_address)?;
+
+            CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+                exists.gfot_token_address = Some(gfot_token_address.clone());
+                Ok(exists)
+            })?;
+
+            Ok(Response::new()
+                .add_attribute("action", "instantiate_gfot")
+                .add_attribute("gfot_token_address", gfot_token_address))
+        }
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { new_owner , gfot_token_address} 
-            => execute_update_config(deps, info, new_owner, gfot_token_address),
-        ExecuteMsg::Receive(msg) => try_receive(deps, info, msg),
+        ExecuteMsg::UpdateConfig { gfot_token_address } => execute_update_config(deps, info, gfot_token_address),
+        ExecuteMsg::ProposeOwner { address, expiry } => execute_propose_owner(deps, env, info, address, expiry),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelProposal {} => execute_cancel_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+        ExecuteMsg::Receive(msg) => try_receive(deps, env, info, msg),
+        ExecuteMsg::HandOffMinter { new_minter } => execute_hand_off_minter(deps, info, new_minter),
+        ExecuteMsg::RegisterAirdrop { merkle_root, start, expiration, total_amount, kind }
+            => execute_register_airdrop(deps, env, info, merkle_root, start, expiration, total_amount, kind),
+        ExecuteMsg::ClaimAirdrop { stage, amount, proof }
+            => execute_claim_airdrop(deps, env, info, stage, amount, proof),
+        ExecuteMsg::BurnUnclaimed { stage } => execute_burn_unclaimed(deps, env, info, stage),
+        ExecuteMsg::UpdateVesting { vesting } => execute_update_vesting(deps, info, vesting),
+        ExecuteMsg::ClaimVested {} => execute_claim_vested(deps, env, info),
+        ExecuteMsg::ProposeCurveParams { curve } => execute_propose_curve_params(deps, env, info, curve),
+        ExecuteMsg::ApplyCurveParams {} => execute_apply_curve_params(deps, env, info),
+        ExecuteMsg::CancelCurveParams {} => execute_cancel_curve_params(deps, info),
+        ExecuteMsg::UpdateFee { fee } => execute_update_fee(deps, info, fee),
+        ExecuteMsg::WithdrawFees { recipient } => execute_withdraw_fees(deps, info, recipient),
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::Pause { flags } => execute_set_paused(deps, info, flags, true),
+        ExecuteMsg::Unpause { flags } => execute_set_paused(deps, info, flags, false),
     }
 }
 
+fn default_curve() -> CurveParams {
+    CurveParams {
+        start_rate: Uint128::from(BFOT_START_AMOUNT),
+        step_amount: Uint128::from(STEP_AMOUNT),
+        max_steps: None,
+        shape: CurveShape::Linear {},
+    }
+}
+
+fn check_curve(curve: &CurveParams) -> Result<(), ContractError> {
+    let valid = match &curve.shape {
+        CurveShape::Linear {} => true,
+        CurveShape::Exponential { growth } => {
+            //a start rate the growth rounds to nothing would stay flat
+            *growth >= MIN_EXPONENTIAL_GROWTH
+                && *growth <= MAX_EXPONENTIAL_GROWTH
+                && !(curve.start_rate * *growth).is_zero()
+                && curve.max_steps.is_some_and(|steps| steps <= MAX_EXPONENTIAL_STEPS)
+        }
+        CurveShape::PiecewiseLinear { breakpoints } => {
+            breakpoints.first().is_some_and(|b| b.step == 0)
+                && breakpoints.windows(2).all(|w| w[0].step < w[1].step)
+        }
+        CurveShape::Sigmoid { max_rate, width } => *max_rate >= curve.start_rate && *width > 0,
+    };
+    if !valid || curve.step_amount.is_zero() || curve.start_rate.is_zero() {
+        return Err(ContractError::InvalidCurve {})
+    }
+    //selling the last step prices the one after it, so that has to fit as well
+    if let Some(max_steps) = curve.max_steps {
+        if build_curve(curve).steps_cost(max_steps as u128 + 1).is_err() {
+            return Err(ContractError::CurveOverflow { max_steps })
+        }
+    }
+    Ok(())
+}
+
+//rate of the step cfg.supply is in and the bfot left until that step is sold
+fn update_curve_position(cfg: &mut Config, curve: &dyn Curve) -> StdResult<()> {
+    let steps = (cfg.supply / cfg.curve.step_amount).u128();
+    cfg.rate = curve.step_rate(steps)?;
+    cfg.left = curve.steps_cost(steps + 1)?.checked_sub(curve.cost(cfg.supply)?)?;
+    Ok(())
+}
+
+//move cfg.supply along the curve and return (bfot_burn_amount, gfot_send_amount, refund_amount)
+//only whole gFOT units are minted, the bFOT that can't buy one more unit is refunded
+pub fn calc_gfot_amount(
+    cfg: &mut Config,
+    bfot_amount: Uint128
+) -> StdResult<(Uint128, Uint128, Uint128)> {
+    let curve = build_curve(&cfg.curve);
+
+    let start_cost = curve.cost(cfg.supply)?;
+    let mut end_cost = start_cost.checked_add(bfot_amount)?;
+    // nothing is sold past the last step, nor priced further than the step after it
+    let max_supply = match cfg.curve.max_steps {
+        Some(max_steps) => {
+            end_cost = end_cost.min(curve.steps_cost(max_steps as u128)?.max(start_cost));
+            Some(Uint128::from(max_steps).checked_mul(cfg.curve.step_amount)?)
+        }
+        None => None
+    };
+    let mut supply = curve.supply_for(end_cost)?;
+    if let Some(max_supply) = max_supply {
+        supply = supply.min(max_supply).max(cfg.supply);
+    }
+
+    let bfot_burn_amount = curve.cost(supply)?.checked_sub(start_cost)?;
+    let gfot_send_amount = supply.checked_sub(cfg.supply)?;
+    let refund_amount = bfot_amount.checked_sub(bfot_burn_amount)?;
+
+    cfg.supply = supply;
+    update_curve_position(cfg, curve.as_ref())?;
+
+    Ok((bfot_burn_amount, gfot_send_amount, refund_amount))
+}
+
+//fee taken from the minted gFOT, rounded down
+pub fn calc_fee(amount: Uint128, fee_bps: u64) -> Uint128 {
+    amount.multiply_ratio(fee_bps, BPS_DENOMINATOR)
+}
+
+fn check_fee(fee: &FeeConfig) -> Result<(), ContractError> {
+    if fee.bps > MAX_FEE_BPS {
+        return Err(ContractError::FeeTooHigh { max_bps: MAX_FEE_BPS })
+    }
+    if fee.referral_bps as u128 > BPS_DENOMINATOR {
+        return Err(ContractError::ReferralTooHigh { max_bps: BPS_DENOMINATOR as u64 })
+    }
+    Ok(())
+}
+
 pub fn try_receive(
     deps: DepsMut, 
+    env: Env,
     info: MessageInfo, 
     wrapper: Cw20ReceiveMsg
 ) -> Result<Response, ContractError> {
@@ -94,78 +307,155 @@ pub fn try_receive(
     //     }
     // }
 
-    // let fot_token_info: TokenInfoResponse =
-    //     deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-    //         contract_addr: cfg.bfot_token_address.clone().into(),
-    //         msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
-    //     }))?;
-
-    // let gfot_left_amount = Uint128::from(fot_token_info.total_supply);
     let user_addr = &deps.api.addr_validate(&wrapper.sender)?;
 
 
     if info.sender == cfg.bfot_token_address {
 
-        // input bfot amount
-        let mut bfot_received_amount = wrapper.amount;
-        let mut gfot_send_amount = Uint128::zero();
-        let mut bfot_burn_amount = Uint128::zero();
-
-        while bfot_received_amount > Uint128::zero() {
-            
-            if cfg.left < bfot_received_amount {
-                //calculate the first slice 
-                bfot_received_amount -= cfg.left;
-                bfot_burn_amount += cfg.left;
-                gfot_send_amount += cfg.left * Uint128::from(STEP_AMOUNT) / cfg.rate;
-                
-                cfg.rate += Uint128::from(STEP_AMOUNT);
-                cfg.left = cfg.rate;
-
-                if cfg.left >= bfot_received_amount {
-                    continue;
+        if let Some(successor) = cfg.successor.clone() {
+            return Err(ContractError::MintingFrozen { successor: successor.into() })
+        }
+        if PAUSE.may_load(deps.storage)?.unwrap_or_default().paused.mints {
+            return Err(ContractError::Paused { action: "mints".to_string() })
+        }
+
+        let mut staking_contract = None;
+        let mut referrer = None;
+        if !wrapper.msg.is_empty() {
+            match from_binary(&wrapper.msg)? {
+                ReceiveMsg::MintAndStake { staking_contract: address, referrer: by } => {
+                    if cfg.vesting.is_some() {
+                        return Err(ContractError::StakeWhileVesting {})
+                    }
+                    staking_contract = Some(deps.api.addr_validate(&address)?);
+                    referrer = by;
                 }
-                
-                //now use the quadratic equation
-                let a = bfot_received_amount.u128() / STEP_AMOUNT;
-                let b = cfg.rate.u128() / STEP_AMOUNT;
-                let mut n = (4 * b * b - 4 * b + 1 + 8 * a).integer_sqrt();
-                n = n - 2 * b + 1;
-                n = n / 2;
-                
-                // This n is the floor value, so use this
-                cfg.rate += Uint128::from(n).checked_mul(Uint128::from(STEP_AMOUNT)).unwrap();
-                cfg.left = cfg.rate;
-
-                let bfot_sum = n * b + n * (n - 1) / 2;
-                let bfot_amount = Uint128::from(bfot_sum).checked_mul(Uint128::from(STEP_AMOUNT)).unwrap();
-                bfot_burn_amount += bfot_amount;
-                gfot_send_amount += Uint128::from(n).checked_mul(Uint128::from(STEP_AMOUNT)).unwrap();
-                bfot_received_amount -= bfot_amount;
-
-            } else {
-                cfg.left -= bfot_received_amount;
-                bfot_burn_amount += bfot_received_amount;
-                gfot_send_amount += bfot_received_amount * Uint128::from(STEP_AMOUNT) / cfg.rate;
-                bfot_received_amount = Uint128::zero();
+                ReceiveMsg::Mint { referrer: by } => referrer = by,
+                ReceiveMsg::Fot {} | ReceiveMsg::Bfot {} => {}
             }
         }
-        
-        cfg.gfot_sent_amount += gfot_send_amount;
+        let referrer = referrer.map(|r| deps.api.addr_validate(&r)).transpose()?;
+
+        let (bfot_burn_amount, gfot_mint_amount, refund_amount) =
+            calc_gfot_amount(&mut cfg, wrapper.amount)?;
+        //the fee comes out of the minted gFOT
+        let fee_amount = calc_fee(gfot_mint_amount, cfg.fee_bps);
+        let gfot_send_amount = gfot_mint_amount - fee_amount;
+
+        //the referrer's share comes out of the fee
+        let referrer = resolve_referrer(deps.storage, user_addr, referrer)?;
+        let referral_amount = match &referrer {
+            Some(referrer) => {
+                let referral_amount = calc_fee(fee_amount, cfg.fee_referral_bps);
+                REFERRALS.update(deps.storage, referrer, |info| -> StdResult<_> {
+                    let mut info = info.unwrap_or_default();
+                    info.reward_amount = info.reward_amount.checked_add(referral_amount)?;
+                    Ok(info)
+                })?;
+                referral_amount
+            }
+            None => Uint128::zero(),
+        };
+        let fee_amount = fee_amount - referral_amount;
+
+        cfg.gfot_sent_amount += gfot_mint_amount;
         cfg.bfot_burn_amount += bfot_burn_amount;
+        if cfg.fee_treasury.is_none() {
+            cfg.fee_kept += fee_amount;
+        }
         
         CONFIG.save(deps.storage, &cfg)?;
+
+        if bfot_burn_amount > Uint128::zero() {
+            let now = env.block.time.seconds();
+            minters().update(deps.storage, user_addr, |minter| -> StdResult<_> {
+                let mut minter = minter.unwrap_or(MinterInfo {
+                    bfot_burned: Uint128::zero(),
+                    gfot_minted: Uint128::zero(),
+                    mint_count: 0,
+                    first_mint: now,
+                    last_mint: now
+                });
+                minter.bfot_burned += bfot_burn_amount;
+                minter.gfot_minted += gfot_send_amount;
+                minter.mint_count += 1;
+                minter.last_mint = now;
+                Ok(minter)
+            })?;
+        }
         
+        // with vesting on, the gFOT is minted into escrow here
+        let mut recipient = user_addr.clone();
+        match &cfg.vesting {
+            Some(vesting) if gfot_send_amount > Uint128::zero() => {
+                let mut schedules = VESTING.may_load(deps.storage, user_addr)?.unwrap_or_default();
+                schedules.push(VestingSchedule {
+                    amount: gfot_send_amount,
+                    claimed: Uint128::zero(),
+                    start: env.block.time.seconds(),
+                    cliff: vesting.cliff,
+                    duration: vesting.duration
+                });
+                VESTING.save(deps.storage, user_addr, &schedules)?;
+                recipient = env.contract.address.clone();
+            }
+            _ => {}
+        }
+
+        // minted here first and then sent into the staking contract for the user
+        if staking_contract.is_some() {
+            recipient = env.contract.address.clone();
+        }
+
         let mut messages:Vec<CosmosMsg> = vec![];
         if gfot_send_amount > Uint128::zero() {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: cfg.gfot_token_address.into(),
+                contract_addr: gfot_token(&cfg)?.into(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Mint {
-                    recipient: user_addr.clone().into(),
+                    recipient: recipient.into(),
                     amount: gfot_send_amount,
                 })?,
             }));
+
+            if let Some(staking_contract) = &staking_contract {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: gfot_token(&cfg)?.into(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Send {
+                        contract: staking_contract.into(),
+                        amount: gfot_send_amount,
+                        msg: to_binary(&StakingReceiveMsg::Stake {
+                            staker: Some(user_addr.into()),
+                        })?,
+                    })?,
+                }));
+            }
+        }
+
+        if let Some(referrer) = &referrer {
+            if referral_amount > Uint128::zero() {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: gfot_token(&cfg)?.into(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Mint {
+                        recipient: referrer.into(),
+                        amount: referral_amount,
+                    })?,
+                }));
+            }
+        }
+
+        if fee_amount > Uint128::zero() {
+            let fee_recipient = cfg.fee_treasury.clone().unwrap_or_else(|| env.contract.address.clone());
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: gfot_token(&cfg)?.into(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: fee_recipient.into(),
+                    amount: fee_amount,
+                })?,
+            }));
         }
 
         if bfot_burn_amount > Uint128::zero() {
@@ -178,14 +468,33 @@ pub fn try_receive(
             }));
         }
 
-        return Ok(Response::new()
+        if refund_amount > Uint128::zero() {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: cfg.bfot_token_address.clone().into(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: user_addr.clone().into(),
+                    amount: refund_amount,
+                })?,
+            }));
+        }
+
+        let mut res = Response::new()
             .add_messages(messages)
             .add_attributes(vec![
                 attr("action", "send_gfot_burn_bfot"),
                 attr("address", user_addr),
                 attr("bfot_burn_amount", bfot_burn_amount),
                 attr("gfot_send_amount", gfot_send_amount),
-            ]));
+                attr("fee_amount", fee_amount),
+                attr("referrer", referrer.map_or_else(String::new, |r| r.into())),
+                attr("referral_amount", referral_amount),
+                attr("refund_amount", refund_amount),
+            ]);
+        if let Some(staking_contract) = staking_contract {
+            res = res.add_attribute("staking_contract", staking_contract);
+        }
+        return Ok(res);
 
     } 
      else {
@@ -194,6 +503,45 @@ pub fn try_receive(
 }
 
 
+//the referrer of a referred address, linking the given one on its first referred swap
+fn resolve_referrer(
+    storage: &mut dyn Storage,
+    referred: &Addr,
+    referrer: Option<Addr>
+) -> Result<Option<Addr>, ContractError> {
+    //an address keeps its first referrer, one passed on a later swap is ignored
+    if let Some(existing) = REFERRERS.may_load(storage, referred)? {
+        return Ok(Some(existing))
+    }
+    let referrer = match referrer {
+        Some(referrer) => referrer,
+        None => return Ok(None),
+    };
+    if referrer == *referred {
+        return Err(ContractError::SelfReferral {})
+    }
+    //walk up the referrer's chain, the referred address showing up would close a loop
+    let mut next = REFERRERS.may_load(storage, &referrer)?;
+    let mut depth = 0u32;
+    while let Some(upline) = next {
+        if upline == *referred {
+            return Err(ContractError::ReferralLoop {})
+        }
+        depth += 1;
+        if depth > MAX_REFERRAL_DEPTH {
+            return Err(ContractError::ReferralChainTooDeep { max_depth: MAX_REFERRAL_DEPTH })
+        }
+        next = REFERRERS.may_load(storage, &upline)?;
+    }
+    REFERRERS.save(storage, referred, &referrer)?;
+    REFERRALS.update(storage, &referrer, |info| -> StdResult<_> {
+        let mut info = info.unwrap_or_default();
+        info.referred_count += 1;
+        Ok(info)
+    })?;
+    Ok(Some(referrer))
+}
+
 pub fn check_owner(
     deps: &DepsMut,
     info: &MessageInfo
@@ -206,33 +554,66 @@ pub fn check_owner(
     Ok(Response::new().add_attribute("action", "check_owner"))
 }
 
+//guardian or owner, the ones who can halt mints and claims
+pub fn check_guardian(
+    deps: &DepsMut,
+    info: &MessageInfo
+) -> Result<PauseState, ContractError> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    if pause.guardian.as_ref() != Some(&info.sender) {
+        check_owner(deps, info)?;
+    }
+    Ok(pause)
+}
+
+pub fn execute_update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    let guardian = guardian.map(|g| deps.api.addr_validate(&g)).transpose()?;
+    let mut pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    pause.guardian = guardian;
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new().add_attribute("action", "update_guardian"))
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    flags: PauseFlags,
+    paused: bool
+) -> Result<Response, ContractError> {
+    let mut pause = check_guardian(&deps, &info)?;
+    if flags.mints {
+        pause.paused.mints = paused;
+    }
+    if flags.claims {
+        pause.paused.claims = paused;
+    }
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", if paused { "pause" } else { "unpause" }),
+        attr("mints", pause.paused.mints.to_string()),
+        attr("claims", pause.paused.claims.to_string()),
+    ]))
+}
+
 pub fn execute_update_config(
     deps: DepsMut,
     info: MessageInfo,
-    new_owner: Option<String>,
     gfot_token_address: Option<Addr>
 ) -> Result<Response, ContractError> {
     // authorize owner
     check_owner(&deps, &info)?;
-    
-    //test code for checking if check_owner works well
-    // return Err(ContractError::InvalidInput {});
-    // if owner some validated to addr, otherwise set to none
-    let mut tmp_owner = None;
-    if let Some(addr) = new_owner {
-        tmp_owner = Some(deps.api.addr_validate(&addr)?)
-    }
-
-    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
-        exists.owner = tmp_owner;
-        Ok(exists)
-    })?;
-    
-    
 
     if let Some(new_address) = gfot_token_address {
         CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
-            exists.gfot_token_address = new_address;
+            exists.gfot_token_address = Some(new_address);
             Ok(exists)
         })?;
     }
@@ -240,77 +621,767 @@ pub fn execute_update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+pub fn execute_propose_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expiry: Option<Expiration>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    let owner = deps.api.addr_validate(&address)?;
+    let expiry = expiry.unwrap_or_default();
+    if expiry.is_expired(&env.block) {
+        return Err(ContractError::OwnerProposalExpired { expiry })
+    }
+    OWNER_PROPOSAL.save(deps.storage, &OwnerProposal { owner: owner.clone(), expiry })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+        attr("expiry", expiry.to_string()),
+    ]))
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let proposal = OWNER_PROPOSAL.may_load(deps.storage)?.ok_or(ContractError::NoOwnerProposal {})?;
+    if info.sender != proposal.owner {
+        return Err(ContractError::Unauthorized {})
+    }
+    if proposal.expiry.is_expired(&env.block) {
+        return Err(ContractError::OwnerProposalExpired { expiry: proposal.expiry })
+    }
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.owner = Some(proposal.owner);
+        Ok(exists)
+    })?;
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender),
+    ]))
+}
+
+pub fn execute_cancel_proposal(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    if OWNER_PROPOSAL.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoOwnerProposal {})
+    }
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_proposal"))
+}
+
+pub fn execute_renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.owner = None;
+        Ok(exists)
+    })?;
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
+}
+
+pub fn execute_hand_off_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_minter: String
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    if let Some(successor) = cfg.successor {
+        return Err(ContractError::MintingFrozen { successor: successor.into() })
+    }
+
+    let new_minter = deps.api.addr_validate(&new_minter)?;
+    cfg.successor = Some(new_minter.clone());
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: gfot_token(&cfg)?.into(),
+            funds: vec![],
+            msg: to_binary(&Cw20MinterMsg::UpdateMinter {
+                new_minter: Some(new_minter.to_string()),
+            })?,
+        })
+        .add_attributes(vec![
+            attr("action", "hand_off_minter"),
+            attr("new_minter", new_minter),
+        ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_register_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: String,
+    start: Option<Scheduled>,
+    expiration: Option<Expiration>,
+    total_amount: Uint128,
+    kind: AirdropKind
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    if let Some(successor) = cfg.successor {
+        return Err(ContractError::MintingFrozen { successor: successor.into() })
+    }
+
+    // check merkle root length
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root, &mut root_buf)?;
+
+    let stage = LATEST_STAGE
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(1)
+        .ok_or(ContractError::StageLimitReached { max_stage: u8::MAX })?;
+    STAGES.save(deps.storage, stage, &Stage {
+        merkle_root: merkle_root.clone(),
+        start,
+        expiration,
+        kind: kind.clone(),
+        total_amount,
+        claimed_amount: Uint128::zero()
+    })?;
+    LATEST_STAGE.save(deps.storage, &stage)?;
+
+    // gFOT stages pay out of an escrow held by this contract
+    let mut messages:Vec<CosmosMsg> = vec![];
+    if kind == AirdropKind::Gfot && total_amount > Uint128::zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: gfot_token(&cfg)?.into(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: env.contract.address.into(),
+                amount: total_amount,
+            })?,
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "register_airdrop"),
+            attr("stage", stage.to_string()),
+            attr("merkle_root", merkle_root),
+            attr("total_amount", total_amount),
+        ]))
+}
+
+pub fn execute_claim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u8,
+    amount: Uint128,
+    proof: Vec<String>
+) -> Result<Response, ContractError> {
+    let paused = PAUSE.may_load(deps.storage)?.unwrap_or_default().paused;
+    if paused.claims {
+        return Err(ContractError::Paused { action: "claims".to_string() })
+    }
+    let mut airdrop = STAGES.load(deps.storage, stage)?;
+    let mut cfg = CONFIG.load(deps.storage)?;
+    // curve credits mint like a burn does
+    if airdrop.kind == AirdropKind::Curve {
+        if let Some(successor) = cfg.successor {
+            return Err(ContractError::MintingFrozen { successor: successor.into() })
+        }
+        if paused.mints {
+            return Err(ContractError::Paused { action: "mints".to_string() })
+        }
+    }
+
+    // airdrop begun
+    if let Some(start) = airdrop.start {
+        if !start.is_triggered(&env.block) {
+            return Err(ContractError::StageNotBegun { stage, start })
+        }
+    }
+    // not expired
+    if let Some(expiration) = airdrop.expiration {
+        if expiration.is_expired(&env.block) {
+            return Err(ContractError::StageExpired { stage, expiration })
+        }
+    }
+
+    // verify not claimed
+    if CLAIMS.may_load(deps.storage, (stage, &info.sender))?.unwrap_or(false) {
+        return Err(ContractError::Claimed {})
+    }
+
+    verify_merkle_proof(&airdrop.merkle_root, &info.sender, amount, proof)?;
+
+    airdrop.claimed_amount = airdrop.claimed_amount.checked_add(amount).map_err(StdError::from)?;
+    if airdrop.claimed_amount > airdrop.total_amount {
+        return Err(ContractError::StageAmountExceeded { stage })
+    }
+    STAGES.save(deps.storage, stage, &airdrop)?;
+    CLAIMS.save(deps.storage, (stage, &info.sender), &true)?;
+
+    // both kinds count as sent, only curve credits move the curve supply
+    let gfot_send_amount = match airdrop.kind {
+        AirdropKind::Gfot => amount,
+        AirdropKind::Curve => {
+            // the credit buys along the curve, nothing is burned for it
+            let (_, gfot_send_amount, _) = calc_gfot_amount(&mut cfg, amount)?;
+            gfot_send_amount
+        }
+    };
+    cfg.gfot_sent_amount += gfot_send_amount;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    let mut messages:Vec<CosmosMsg> = vec![];
+    if gfot_send_amount > Uint128::zero() {
+        let msg = match airdrop.kind {
+            AirdropKind::Gfot => Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: gfot_send_amount,
+            },
+            AirdropKind::Curve => Cw20ExecuteMsg::Mint {
+                recipient: info.sender.to_string(),
+                amount: gfot_send_amount,
+            },
+        };
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: gfot_token(&cfg)?.into(),
+            funds: vec![],
+            msg: to_binary(&msg)?,
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "claim_airdrop"),
+            attr("stage", stage.to_string()),
+            attr("address", info.sender),
+            attr("amount", amount),
+            attr("gfot_send_amount", gfot_send_amount),
+        ]))
+}
+
+fn verify_merkle_proof(
+    merkle_root: &str,
+    address: &Addr,
+    amount: Uint128,
+    proof: Vec<String>
+) -> Result<(), ContractError> {
+    let user_input = format!("{}{}", address, amount);
+    let hash: [u8; 32] = sha2::Sha256::digest(user_input.as_bytes()).into();
+
+    let hash = proof.into_iter().try_fold(hash, |hash, p| {
+        let mut proof_buf = [0; 32];
+        hex::decode_to_slice(p, &mut proof_buf)?;
+        let mut hashes = [hash, proof_buf];
+        hashes.sort_unstable();
+        Ok::<[u8; 32], ContractError>(sha2::Sha256::digest(&hashes.concat()).into())
+    })?;
+
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(merkle_root, &mut root_buf)?;
+    if root_buf != hash {
+        return Err(ContractError::VerificationFailed {})
+    }
+    Ok(())
+}
+
+pub fn execute_burn_unclaimed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u8
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    let mut airdrop = STAGES.load(deps.storage, stage)?;
+    let expired = airdrop.expiration.is_some_and(|e| e.is_expired(&env.block));
+    if !expired {
+        return Err(ContractError::StageNotExpired { stage })
+    }
+
+    let unclaimed_amount = airdrop.total_amount - airdrop.claimed_amount;
+    airdrop.claimed_amount = airdrop.total_amount;
+    STAGES.save(deps.storage, stage, &airdrop)?;
+
+    // curve stages never minted anything up front
+    let mut messages:Vec<CosmosMsg> = vec![];
+    if airdrop.kind == AirdropKind::Gfot && unclaimed_amount > Uint128::zero() {
+        let cfg = CONFIG.load(deps.storage)?;
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: gfot_token(&cfg)?.into(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                amount: unclaimed_amount,
+            })?,
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "burn_unclaimed"),
+            attr("stage", stage.to_string()),
+            attr("amount", unclaimed_amount),
+        ]))
+}
+
+fn check_vesting(vesting: &VestingParams) -> Result<(), ContractError> {
+    if vesting.cliff > vesting.duration {
+        return Err(ContractError::InvalidVesting {})
+    }
+    Ok(())
+}
+
+//amount of the schedule released at the given time, claimed or not
+fn calc_vested_amount(schedule: &VestingSchedule, now: u64) -> Uint128 {
+    let elapsed = now.saturating_sub(schedule.start);
+    if elapsed < schedule.cliff {
+        return Uint128::zero();
+    }
+    if elapsed >= schedule.duration {
+        return schedule.amount;
+    }
+    schedule.amount.multiply_ratio(elapsed, schedule.duration)
+}
+
+pub fn execute_update_vesting(
+    deps: DepsMut,
+    info: MessageInfo,
+    vesting: Option<VestingParams>
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    if let Some(vesting) = &vesting {
+        check_vesting(vesting)?;
+    }
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.vesting = vesting;
+        Ok(exists)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "update_vesting"))
+}
+
+pub fn execute_claim_vested(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    if PAUSE.may_load(deps.storage)?.unwrap_or_default().paused.claims {
+        return Err(ContractError::Paused { action: "claims".to_string() })
+    }
+    let cfg = CONFIG.load(deps.storage)?;
+    let schedules = VESTING.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+
+    let now = env.block.time.seconds();
+    let mut claim_amount = Uint128::zero();
+    let mut left_schedules = vec![];
+    for mut schedule in schedules {
+        let vested = calc_vested_amount(&schedule, now);
+        claim_amount += vested - schedule.claimed;
+        schedule.claimed = vested;
+        // fully released schedules are dropped
+        if schedule.claimed < schedule.amount {
+            left_schedules.push(schedule);
+        }
+    }
+
+    if claim_amount == Uint128::zero() {
+        return Err(ContractError::NoVested {})
+    }
+
+    if left_schedules.is_empty() {
+        VESTING.remove(deps.storage, &info.sender);
+    } else {
+        VESTING.save(deps.storage, &info.sender, &left_schedules)?;
+    }
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: gfot_token(&cfg)?.into(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: claim_amount,
+            })?,
+        })
+        .add_attributes(vec![
+            attr("action", "claim_vested"),
+            attr("address", info.sender),
+            attr("gfot_amount", claim_amount),
+        ]))
+}
+
+pub fn execute_propose_curve_params(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    curve: CurveParams
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    check_curve(&curve)?;
+
+    let effective_at = env.block.time.seconds() + CURVE_TIMELOCK;
+    PENDING_CURVE.save(deps.storage, &PendingCurveParams { curve, effective_at })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_curve_params"),
+        attr("effective_at", effective_at.to_string()),
+    ]))
+}
+
+pub fn execute_apply_curve_params(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    let pending = PENDING_CURVE.may_load(deps.storage)?.ok_or(ContractError::NoPendingCurve {})?;
+    if env.block.time.seconds() < pending.effective_at {
+        return Err(ContractError::CurveTimelocked { effective_at: pending.effective_at })
+    }
+
+    // the supply sold so far stays, its price is taken from the new curve
+    let mut cfg = CONFIG.load(deps.storage)?;
+    check_curve(&pending.curve)?;
+    cfg.curve = pending.curve;
+    let curve = build_curve(&cfg.curve);
+    update_curve_position(&mut cfg, curve.as_ref())?;
+    CONFIG.save(deps.storage, &cfg)?;
+    PENDING_CURVE.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "apply_curve_params"))
+}
+
+pub fn execute_cancel_curve_params(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    // authorize owner
+    check_owner(&deps, &info)?;
+
+    PENDING_CURVE.may_load(deps.storage)?.ok_or(ContractError::NoPendingCurve {})?;
+    PENDING_CURVE.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_curve_params"))
+}
+
+pub fn execute_update_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee: FeeConfig
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    check_fee(&fee)?;
+
+    let treasury = fee.treasury.as_ref().map(|treasury| deps.api.addr_validate(treasury)).transpose()?;
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.fee_bps = fee.bps;
+        exists.fee_referral_bps = fee.referral_bps;
+        exists.fee_treasury = treasury;
+        Ok(exists)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_fee"),
+        attr("fee_bps", fee.bps.to_string()),
+        attr("referral_bps", fee.referral_bps.to_string()),
+    ]))
+}
+
+pub fn execute_withdraw_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: Option<String>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    let amount = cfg.fee_kept;
+    if amount.is_zero() {
+        return Err(ContractError::NoFees {})
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+    cfg.fee_kept = Uint128::zero();
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: gfot_token(&cfg)?.into(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+        })
+        .add_attributes(vec![
+            attr("action", "withdraw_fees"),
+            attr("recipient", recipient),
+            attr("gfot_amount", amount),
+        ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::ExpectedAmount{bfot_amount} => to_binary(&query_expected_amount(deps, bfot_amount)?)
+        QueryMsg::Status {} => to_binary(&query_status(deps)?),
+        QueryMsg::ExpectedAmount{bfot_amount} => to_binary(&query_expected_amount(deps, bfot_amount)?),
+        QueryMsg::CurvePoints { from_supply, to_supply, points }
+            => to_binary(&query_curve_points(deps, from_supply, to_supply, points)?),
+        QueryMsg::LatestStage {} => to_binary(&query_latest_stage(deps)?),
+        QueryMsg::Stage { stage } => to_binary(&query_stage(deps, stage)?),
+        QueryMsg::IsClaimed { stage, address } => to_binary(&query_is_claimed(deps, stage, address)?),
+        QueryMsg::Vesting { address } => to_binary(&query_vesting(deps, env, address)?),
+        QueryMsg::Minters { start_after, limit, order_by }
+            => to_binary(&query_minters(deps, start_after, limit, order_by)?),
+        QueryMsg::Referrals { referrer } => to_binary(&query_referrals(deps, referrer)?),
     }
 }
 
+pub fn query_status(deps: Deps) -> StdResult<StatusResponse> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    Ok(StatusResponse {
+        guardian: pause.guardian.map(|g| g.into()),
+        paused: pause.paused
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let cfg = CONFIG.load(deps.storage)?;
+
+    // bFOT needed for the next STEP of gFOT from the current position
+    let curve = build_curve(&cfg.curve);
+    let bfot_expected_amount = curve.cost(cfg.supply.checked_add(cfg.curve.step_amount)?)?
+        .checked_sub(curve.cost(cfg.supply)?)?;
+
     Ok(ConfigResponse {
         owner: cfg.owner.map(|o| o.into()),
         bfot_token_address: cfg.bfot_token_address.into(),
-        gfot_token_address: cfg.gfot_token_address.into(),
+        gfot_token_address: cfg.gfot_token_address.map(|a| a.into()),
         bfot_burn_amount: cfg.bfot_burn_amount,
         gfot_sent_amount: cfg.gfot_sent_amount,
-        bfot_expected_amount: cfg.gfot_sent_amount + Uint128::from(BFOT_START_AMOUNT),
+        bfot_expected_amount,
         rate: cfg.rate,
-        left: cfg.left
+        left: cfg.left,
+        supply: cfg.supply,
+        successor: cfg.successor.map(|s| s.into()),
+        vesting: cfg.vesting,
+        curve: cfg.curve,
+        pending_curve: PENDING_CURVE.may_load(deps.storage)?,
+        fee_bps: cfg.fee_bps,
+        fee_treasury: cfg.fee_treasury.map(|t| t.into()),
+        fee_kept: cfg.fee_kept,
+        fee_referral_bps: cfg.fee_referral_bps,
+        pending_owner: OWNER_PROPOSAL.may_load(deps.storage)?
+    })
+}
+
+pub fn query_referrals(deps: Deps, referrer: String) -> StdResult<ReferralsResponse> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    let info = REFERRALS.may_load(deps.storage, &referrer)?.unwrap_or_default();
+    Ok(ReferralsResponse {
+        referrer: referrer.into(),
+        referred_count: info.referred_count,
+        reward_amount: info.reward_amount
     })
 }
 
+//the gFOT token, missing until the reply of its instantiation
+fn gfot_token(cfg: &Config) -> Result<Addr, ContractError> {
+    cfg.gfot_token_address.clone().ok_or(ContractError::GfotTokenPending {})
+}
+
+//nothing is minted on the curve once the minter moved
+fn check_not_frozen(cfg: &Config) -> StdResult<()> {
+    match &cfg.successor {
+        Some(successor) => Err(StdError::generic_err(
+            ContractError::MintingFrozen { successor: successor.into() }.to_string()
+        )),
+        None => Ok(())
+    }
+}
+
 pub fn query_expected_amount(deps: Deps, bfot_amount:Uint128) -> StdResult<ExpectedAmountResponse> {
     let mut cfg = CONFIG.load(deps.storage)?;
-    
-    let mut bfot_received_amount = bfot_amount.clone();
-    let mut gfot_send_amount = Uint128::zero();
-    let mut bfot_burn_amount = Uint128::zero();
+    check_not_frozen(&cfg)?;
+    let (bfot_burn_amount, gfot_mint_amount, refund_amount) =
+        calc_gfot_amount(&mut cfg, bfot_amount)?;
+    let fee_amount = calc_fee(gfot_mint_amount, cfg.fee_bps);
 
-    while bfot_received_amount > Uint128::zero() {
-        
-        if cfg.left < bfot_received_amount {
-            //calculate the first slice 
-            bfot_received_amount -= cfg.left;
-            bfot_burn_amount += cfg.left;
-            gfot_send_amount += cfg.left * Uint128::from(STEP_AMOUNT) / cfg.rate;
-            
-            cfg.rate += Uint128::from(STEP_AMOUNT);
-            cfg.left = cfg.rate;
-
-            if cfg.left >= bfot_received_amount {
-                continue;
-            }
-            
-            //now use the quadratic equation
-            let a = bfot_received_amount.u128() / STEP_AMOUNT;
-            let b = cfg.rate.u128() / STEP_AMOUNT;
-            let mut n = (4 * b * b - 4 * b + 1 + 8 * a).integer_sqrt();
-            n = n - 2 * b + 1;
-            n = n / 2;
-            
-            // This n is the floor value, so use this
-            cfg.rate += Uint128::from(n).checked_mul(Uint128::from(STEP_AMOUNT)).unwrap();
-            cfg.left = cfg.rate;
-
-            let bfot_sum = n * b + n * (n - 1) / 2;
-            let bfot_amount = Uint128::from(bfot_sum).checked_mul(Uint128::from(STEP_AMOUNT)).unwrap();
-            bfot_burn_amount += bfot_amount;
-            gfot_send_amount += Uint128::from(n).checked_mul(Uint128::from(STEP_AMOUNT)).unwrap();
-            bfot_received_amount -= bfot_amount;
-
-        } else {
-            cfg.left -= bfot_received_amount;
-            bfot_burn_amount += bfot_received_amount;
-            gfot_send_amount += bfot_received_amount * Uint128::from(STEP_AMOUNT) / cfg.rate;
-            bfot_received_amount = Uint128::zero();
-        }
-    }
     Ok(ExpectedAmountResponse {
-        bfot_burn_amount: bfot_burn_amount,
-        gfot_send_amount: gfot_send_amount
+        bfot_burn_amount,
+        gfot_send_amount: gfot_mint_amount - fee_amount,
+        fee_amount,
+        refund_amount
+    })
+}
+
+fn curve_point(curve: &dyn Curve, supply: Uint128) -> StdResult<CurvePoint> {
+    let step_cost = curve.step_rate((supply / curve.step_amount()).u128())?;
+    let price = Decimal256::checked_from_ratio(step_cost, curve.step_amount())
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(CurvePoint {
+        supply,
+        price,
+        step_cost,
+        cost: curve.cost(supply)?
+    })
+}
+
+pub fn query_curve_points(
+    deps: Deps,
+    from_supply: Uint128,
+    to_supply: Uint128,
+    points: u32
+) -> StdResult<CurvePointsResponse> {
+    if from_supply > to_supply {
+        return Err(StdError::generic_err("from_supply must not be above to_supply"));
+    }
+    if !(2..=MAX_CURVE_POINTS).contains(&points) {
+        return Err(StdError::generic_err(format!("points must be between 2 and {}", MAX_CURVE_POINTS)));
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let curve = build_curve(&cfg.curve);
+    let intervals = Uint128::from(points - 1);
+    let width = to_supply - from_supply;
+    let points = (0..points)
+        .map(|i| {
+            let offset = width.multiply_ratio(Uint128::from(i), intervals);
+            curve_point(curve.as_ref(), from_supply + offset)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CurvePointsResponse {
+        points,
+        current: curve_point(curve.as_ref(), cfg.supply)?,
+        frozen: cfg.successor.is_some()
+    })
+}
+
+pub fn query_latest_stage(deps: Deps) -> StdResult<LatestStageResponse> {
+    let latest_stage = LATEST_STAGE.may_load(deps.storage)?.unwrap_or_default();
+    Ok(LatestStageResponse { latest_stage })
+}
+
+pub fn query_stage(deps: Deps, stage: u8) -> StdResult<StageResponse> {
+    let airdrop = STAGES.load(deps.storage, stage)?;
+    Ok(StageResponse {
+        stage,
+        merkle_root: airdrop.merkle_root,
+        start: airdrop.start,
+        expiration: airdrop.expiration,
+        kind: airdrop.kind,
+        total_amount: airdrop.total_amount,
+        claimed_amount: airdrop.claimed_amount
+    })
+}
+
+pub fn query_is_claimed(deps: Deps, stage: u8, address: String) -> StdResult<IsClaimedResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let is_claimed = CLAIMS.may_load(deps.storage, (stage, &address))?.unwrap_or(false);
+    Ok(IsClaimedResponse { is_claimed })
+}
+
+pub fn query_vesting(deps: Deps, env: Env, address: String) -> StdResult<VestingResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let schedules = VESTING.may_load(deps.storage, &address)?.unwrap_or_default();
+
+    let now = env.block.time.seconds();
+    let claimable = schedules
+        .iter()
+        .map(|schedule| calc_vested_amount(schedule, now) - schedule.claimed)
+        .sum();
+
+    Ok(VestingResponse {
+        address: address.into(),
+        schedules,
+        claimable
+    })
+}
+
+pub fn query_minters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order_by: Option<MinterOrderBy>
+) -> StdResult<MintersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|address| deps.api.addr_validate(&address)).transpose()?;
+
+    let minters = match order_by.unwrap_or(MinterOrderBy::Address) {
+        MinterOrderBy::Address => {
+            let min = start_after.map(|address| Bound::exclusive(address.as_bytes()));
+            minters()
+                .range(deps.storage, min, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+        MinterOrderBy::Burned => {
+            //index keys are (burned, address), so the bound needs the burned amount of start_after
+            let max = match start_after {
+                Some(address) => {
+                    let minter = minters().load(deps.storage, &address)?;
+                    let mut key = minters().idx.burned
+                        .index_key(minter.bfot_burned.u128().to_be_bytes().to_vec());
+                    key.extend_from_slice(address.as_bytes());
+                    Some(Bound::exclusive(key))
+                }
+                None => None
+            };
+            minters().idx.burned
+                .range(deps.storage, None, max, Order::Descending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+    };
+
+    Ok(MintersResponse {
+        minters: minters
+            .into_iter()
+            .map(|(address, minter)| MinterInfoResponse {
+                address: address.into(),
+                bfot_burned: minter.bfot_burned,
+                gfot_minted: minter.gfot_minted,
+                mint_count: minter.mint_count,
+                first_mint: minter.first_mint,
+                last_mint: minter.last_mint
+            })
+            .collect()
     })
 }
 
@@ -322,5 +1393,637 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
             previous_contract: version.contract,
         });
     }
+
+    // fill in what older deployments don't store yet, rate and left are kept as they are.
+    // before the curve was stored the contract always ran the original linear one,
+    // other curves go through ProposeCurveParams
+    let old = CONFIG_V0.load(deps.storage)?;
+    let curve = old.curve.unwrap_or_else(default_curve);
+    check_curve(&curve)?;
+
+    let supply = match old.supply {
+        Some(supply) => supply,
+        None => original_supply(old.rate, old.left)?,
+    };
+
+    CONFIG.save(deps.storage, &Config {
+        owner: old.owner,
+        bfot_token_address: old.bfot_token_address,
+        gfot_token_address: Some(old.gfot_token_address),
+        bfot_burn_amount: old.bfot_burn_amount,
+        gfot_sent_amount: old.gfot_sent_amount,
+        rate: old.rate,
+        left: old.left,
+        supply,
+        successor: old.successor,
+        vesting: old.vesting,
+        curve,
+        fee_bps: old.fee_bps.unwrap_or_default(),
+        fee_treasury: old.fee_treasury,
+        fee_kept: old.fee_kept.unwrap_or_default(),
+        fee_referral_bps: old.fee_referral_bps.unwrap_or_default()
+    })?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
 }
+
+// gFOT sold on the original curve given its rate and left
+fn original_supply(rate: Uint128, left: Uint128) -> StdResult<Uint128> {
+    let step_amount = Uint128::from(STEP_AMOUNT);
+    let steps = rate.checked_sub(Uint128::from(BFOT_START_AMOUNT))?.checked_div(step_amount)?;
+    let price = rate.checked_div(step_amount)?;
+    let sold = rate.checked_sub(left)?.checked_div(price)?;
+    Ok(steps.checked_mul(step_amount)?.checked_add(sold)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{Decimal, SubMsgResponse, SubMsgResult};
+
+    fn mock_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            owner: None,
+            bfot_token_address: Addr::unchecked("bfot"),
+            gfot_token: GfotTokenInfo::Existing { address: "gfot".to_string() },
+            vesting: None,
+            curve: None,
+            fee: None,
+            guardian: Some("guardian".to_string()),
+        }
+    }
+
+    fn mock_instance_with(msg: InstantiateMsg) -> cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        deps
+    }
+
+    fn mock_instance() -> cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        mock_instance_with(mock_instantiate_msg())
+    }
+
+    // bFOT sent in by the minter, on the default curve a gFOT unit costs 10000 bFOT
+    fn mint(sender: &str, amount: u128, msg: Option<ReceiveMsg>) -> ExecuteMsg {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(amount),
+            msg: msg.map_or_else(Binary::default, |msg| to_binary(&msg).unwrap()),
+        })
+    }
+
+    fn cw20_msg(token: &str, msg: Cw20ExecuteMsg) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            funds: vec![],
+            msg: to_binary(&msg).unwrap(),
+        })
+    }
+
+    fn messages(res: Response) -> Vec<CosmosMsg> {
+        res.messages.into_iter().map(|m| m.msg).collect()
+    }
+
+    // root of a tree holding just this leaf, claimed with an empty proof
+    fn single_leaf_root(address: &str, amount: u128) -> String {
+        hex::encode(sha2::Sha256::digest(format!("{}{}", address, amount).as_bytes()))
+    }
+
+    // config as stored before curve, supply, fees and vesting were added
+    fn baseline_config(rate: u128, left: u128) -> Vec<u8> {
+        format!(
+            r#"{{"owner":"owner","bfot_token_address":"bfot","gfot_token_address":"gfot","bfot_burn_amount":"{}","gfot_sent_amount":"{}","rate":"{}","left":"{}"}}"#,
+            rate - left, 3 * STEP_AMOUNT + 5_000, rate, left
+        ).into_bytes()
+    }
+
+    #[test]
+    fn migrate_baseline_config() {
+        // three steps in, 5000 units of the fourth sold at its price of 10003 per unit
+        let rate = BFOT_START_AMOUNT + 3 * STEP_AMOUNT;
+        let left = rate - 5_000 * 10_003;
+
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.0").unwrap();
+        deps.as_mut().storage.set(b"config", &baseline_config(rate, left));
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.rate, Uint128::from(rate));
+        assert_eq!(config.left, Uint128::from(left));
+        assert_eq!(config.supply, Uint128::from(3 * STEP_AMOUNT + 5_000));
+        assert_eq!(config.curve, default_curve());
+        assert_eq!(config.fee_bps, 0);
+
+        //a rate below the original start errors instead of underflowing
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.0").unwrap();
+        deps.as_mut().storage.set(b"config", &baseline_config(BFOT_START_AMOUNT - 1, 0));
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+    }
+
+    #[test]
+    fn curve_params_wait_for_the_timelock() {
+        let mut deps = mock_instance();
+        let mut env = mock_env();
+        execute(deps.as_mut(), env.clone(), mock_info("bfot", &[]), mint("alice", 100_000, None)).unwrap();
+
+        let curve = CurveParams { start_rate: Uint128::from(2 * BFOT_START_AMOUNT), ..default_curve() };
+        let propose = ExecuteMsg::ProposeCurveParams { curve: curve.clone() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), propose.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), propose.clone()).unwrap();
+        let effective_at = env.block.time.seconds() + CURVE_TIMELOCK;
+        let pending = query_config(deps.as_ref()).unwrap().pending_curve;
+        assert_eq!(pending, Some(PendingCurveParams { curve: curve.clone(), effective_at }));
+
+        env.block.time = env.block.time.plus_seconds(CURVE_TIMELOCK - 1);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::ApplyCurveParams {}).unwrap_err();
+        assert_eq!(err, ContractError::CurveTimelocked { effective_at });
+        env.block.time = env.block.time.plus_seconds(1);
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::ApplyCurveParams {}).unwrap();
+
+        //the 10 units sold stay, the rest of the first step costs twice as much
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!((config.curve, config.pending_curve, config.supply.u128()), (curve, None, 10));
+        assert_eq!(config.rate, Uint128::from(2 * BFOT_START_AMOUNT));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bfot", &[]), mint("alice", 100_000, None)).unwrap();
+        assert!(res.attributes.contains(&attr("gfot_send_amount", "5")));
+
+        //a cancelled proposal can't be applied
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), propose).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::CancelCurveParams {}).unwrap();
+        env.block.time = env.block.time.plus_seconds(CURVE_TIMELOCK);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::ApplyCurveParams {}).unwrap_err();
+        assert_eq!(err, ContractError::NoPendingCurve {});
+        let err = execute(deps.as_mut(), env, mock_info("owner", &[]), ExecuteMsg::CancelCurveParams {}).unwrap_err();
+        assert_eq!(err, ContractError::NoPendingCurve {});
+    }
+
+    #[test]
+    fn fee_comes_out_of_the_minted_gfot() {
+        let fee = |bps, treasury: Option<&str>| FeeConfig { bps, treasury: treasury.map(|t| t.to_string()), referral_bps: 0 };
+        let msg = InstantiateMsg { fee: Some(fee(MAX_FEE_BPS + 1, None)), ..mock_instantiate_msg() };
+        let err = instantiate(mock_dependencies().as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::FeeTooHigh { max_bps: MAX_FEE_BPS });
+        let mut deps = mock_instance_with(InstantiateMsg { fee: Some(fee(500, Some("treasury"))), ..mock_instantiate_msg() });
+
+        //100 units minted, 5% of them to the treasury
+        let expected = query_expected_amount(deps.as_ref(), Uint128::from(1_000_000u128)).unwrap();
+        assert_eq!((expected.gfot_send_amount.u128(), expected.fee_amount.u128()), (95, 5));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint("alice", 1_000_000, None)).unwrap();
+        assert!(res.attributes.contains(&attr("gfot_send_amount", "95")));
+        assert!(res.attributes.contains(&attr("fee_amount", "5")));
+        let mint_to = |recipient: &str, amount: u128| {
+            cw20_msg("gfot", Cw20ExecuteMsg::Mint { recipient: recipient.to_string(), amount: Uint128::from(amount) })
+        };
+        assert_eq!(messages(res), vec![
+            mint_to("alice", 95),
+            mint_to("treasury", 5),
+            cw20_msg("bfot", Cw20ExecuteMsg::Burn { amount: Uint128::from(1_000_000u128) }),
+        ]);
+        assert_eq!(query_config(deps.as_ref()).unwrap().fee_kept, Uint128::zero());
+
+        let update = |fee| ExecuteMsg::UpdateFee { fee };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), update(fee(MAX_FEE_BPS + 1, None))).unwrap_err();
+        assert_eq!(err, ContractError::FeeTooHigh { max_bps: MAX_FEE_BPS });
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), update(fee(MAX_FEE_BPS, None))).unwrap();
+
+        //without a treasury the fee is minted here until the owner withdraws it
+        let env = mock_env();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bfot", &[]), mint("alice", 1_000_000, None)).unwrap();
+        assert_eq!(messages(res)[1], mint_to(env.contract.address.as_str(), 10));
+        assert_eq!(query_config(deps.as_ref()).unwrap().fee_kept, Uint128::from(10u128));
+
+        let withdraw = ExecuteMsg::WithdrawFees { recipient: Some("treasury".to_string()) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), withdraw.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), withdraw.clone()).unwrap();
+        let transfer = Cw20ExecuteMsg::Transfer { recipient: "treasury".to_string(), amount: Uint128::from(10u128) };
+        assert_eq!(messages(res), vec![cw20_msg("gfot", transfer)]);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), withdraw).unwrap_err();
+        assert_eq!(err, ContractError::NoFees {});
+    }
+
+    #[test]
+    fn curve_points_sample_the_curve() {
+        // steps of 10 units, the first costing 1000 bFOT and every next one 10 more
+        let params = CurveParams {
+            start_rate: Uint128::from(1000u128),
+            step_amount: Uint128::from(10u128),
+            max_steps: None,
+            shape: CurveShape::Linear {},
+        };
+        let mut deps = mock_instance_with(InstantiateMsg { curve: Some(params.clone()), ..mock_instantiate_msg() });
+        let curve = build_curve(&params);
+        //two steps and 5 units of the third at 102 each
+        execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint("alice", 1000 + 1010 + 510, None)).unwrap();
+
+        let chart = query_curve_points(deps.as_ref(), Uint128::zero(), Uint128::from(100u128), 5).unwrap();
+        let supplies: Vec<_> = chart.points.iter().map(|p| p.supply.u128()).collect();
+        assert_eq!(supplies, vec![0, 25, 50, 75, 100]);
+        for point in &chart.points {
+            assert_eq!(point.cost, curve.cost(point.supply).unwrap());
+        }
+        let at_25 = CurvePoint {
+            supply: Uint128::from(25u128),
+            price: Decimal256::from_ratio(102u128, 1u128),
+            step_cost: Uint128::from(1020u128),
+            cost: Uint128::from(2520u128),
+        };
+        assert_eq!(chart.points[1], at_25);
+        assert_eq!(chart.points[4].price, Decimal256::from_ratio(110u128, 1u128));
+        assert_eq!(chart.current, at_25);
+        assert!(!chart.frozen);
+
+        //uneven widths round each sample down, the ends stay exact
+        let chart = query_curve_points(deps.as_ref(), Uint128::from(7u128), Uint128::from(17u128), 4).unwrap();
+        let supplies: Vec<_> = chart.points.iter().map(|p| p.supply.u128()).collect();
+        assert_eq!(supplies, vec![7, 10, 13, 17]);
+        assert_eq!(chart.points[0].price, Decimal256::from_ratio(100u128, 1u128));
+        assert_eq!(chart.points[3].price, Decimal256::from_ratio(101u128, 1u128));
+
+        assert!(query_curve_points(deps.as_ref(), Uint128::from(10u128), Uint128::from(7u128), 4).is_err());
+        assert!(query_curve_points(deps.as_ref(), Uint128::zero(), Uint128::from(7u128), 1).is_err());
+        assert!(query_curve_points(deps.as_ref(), Uint128::zero(), Uint128::from(7u128), MAX_CURVE_POINTS + 1).is_err());
+    }
+
+    #[test]
+    fn referrer_earns_a_share_of_every_fee() {
+        let fee = FeeConfig { bps: 1000, treasury: Some("treasury".to_string()), referral_bps: 5000 };
+        let mut deps = mock_instance_with(InstantiateMsg { fee: Some(fee), ..mock_instantiate_msg() });
+        let mint_msg = |referrer: Option<&str>| mint("alice", 1_000_000, Some(ReceiveMsg::Mint {
+            referrer: referrer.map(|r| r.to_string()),
+        }));
+        let mint_to = |recipient: &str, amount: u128| {
+            cw20_msg("gfot", Cw20ExecuteMsg::Mint { recipient: recipient.to_string(), amount: Uint128::from(amount) })
+        };
+
+        //100 units, a 10 fee split between the referrer and the treasury
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint_msg(Some("referrer"))).unwrap();
+        assert!(res.attributes.contains(&attr("referral_amount", "5")));
+        assert_eq!(messages(res), vec![
+            mint_to("alice", 90),
+            mint_to("referrer", 5),
+            mint_to("treasury", 5),
+            cw20_msg("bfot", Cw20ExecuteMsg::Burn { amount: Uint128::from(1_000_000u128) }),
+        ]);
+
+        //later mints pay the same referrer, whoever is passed
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint_msg(None)).unwrap();
+        assert_eq!(messages(res)[1], mint_to("referrer", 5));
+        let referrals = query_referrals(deps.as_ref(), "referrer".to_string()).unwrap();
+        assert_eq!((referrals.referred_count, referrals.reward_amount.u128()), (1, 10));
+    }
+
+    #[test]
+    fn exponential_curve_is_bounded() {
+        let curve = |growth: Decimal, max_steps: Option<u64>| CurveParams {
+            max_steps,
+            shape: CurveShape::Exponential { growth },
+            ..default_curve()
+        };
+        assert!(check_curve(&curve(Decimal::permille(3), Some(2_000))).is_ok());
+        for invalid in [
+            curve(Decimal::permille(3), None),
+            //0.1% of a start rate under 1000 rounds to nothing
+            CurveParams { start_rate: Uint128::from(999u128), ..curve(MIN_EXPONENTIAL_GROWTH, Some(100)) },
+            curve(Decimal::permille(3), Some(MAX_EXPONENTIAL_STEPS + 1)),
+            curve(Decimal::from_ratio(1u128, 10_000u128), Some(100)),
+            curve(Decimal::percent(101), Some(100)),
+        ] {
+            assert_eq!(check_curve(&invalid), Err(ContractError::InvalidCurve {}));
+        }
+        //doubling every step overflows long before the step cap
+        assert_eq!(
+            check_curve(&curve(MAX_EXPONENTIAL_GROWTH, Some(MAX_EXPONENTIAL_STEPS))),
+            Err(ContractError::CurveOverflow { max_steps: MAX_EXPONENTIAL_STEPS })
+        );
+        let last = |max_steps| build_curve(&curve(MAX_EXPONENTIAL_GROWTH, Some(max_steps))).steps_cost(max_steps as u128 + 1);
+        let max_steps = (1..MAX_EXPONENTIAL_STEPS).take_while(|&steps| last(steps).is_ok()).last().unwrap();
+        assert!(check_curve(&curve(MAX_EXPONENTIAL_GROWTH, Some(max_steps))).is_ok());
+        assert_eq!(
+            check_curve(&curve(MAX_EXPONENTIAL_GROWTH, Some(max_steps + 1))),
+            Err(ContractError::CurveOverflow { max_steps: max_steps + 1 })
+        );
+    }
+
+    // leaves sha256("alice1000") and sha256("bob2500")
+    const ALICE_LEAF: &str = "cbc78ae2b4d961ceea458a25883e33ddb8cd4d40f7bf2b8f9b48db93ac90de03";
+    const BOB_LEAF: &str = "add477c8b239268827e2135534c5208e6a598fa1e73cfba0511de9806d219f0f";
+    const ROOT: &str = "29eb645f24d9b8d17ca34f5d2575ac1718f5ab826a5b637d8e872dda58185532";
+
+    #[test]
+    fn merkle_proof_vector() {
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        verify_merkle_proof(ROOT, &alice, Uint128::from(1000u128), vec![BOB_LEAF.to_string()]).unwrap();
+        verify_merkle_proof(ROOT, &bob, Uint128::from(2500u128), vec![ALICE_LEAF.to_string()]).unwrap();
+
+        let err = verify_merkle_proof(ROOT, &alice, Uint128::from(1001u128), vec![BOB_LEAF.to_string()]).unwrap_err();
+        assert_eq!(err, ContractError::VerificationFailed {});
+        let err = verify_merkle_proof(ROOT, &alice, Uint128::from(1000u128), vec![]).unwrap_err();
+        assert_eq!(err, ContractError::VerificationFailed {});
+        let err = verify_merkle_proof(ROOT, &alice, Uint128::from(1000u128), vec!["zz".to_string()]).unwrap_err();
+        assert!(matches!(err, ContractError::Hex(_)));
+    }
+
+    #[test]
+    fn airdrop_claims_within_window_once() {
+        let mut deps = mock_instance();
+        let mut env = mock_env();
+        let height = env.block.height;
+        let register = ExecuteMsg::RegisterAirdrop {
+            merkle_root: ROOT.to_string(),
+            start: Some(Scheduled::AtHeight(height + 5)),
+            expiration: Some(Expiration::AtHeight(height + 10)),
+            total_amount: Uint128::from(3500u128),
+            kind: AirdropKind::Gfot,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), register).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let claim = |amount: u128, proof: &str| ExecuteMsg::ClaimAirdrop {
+            stage: 1,
+            amount: Uint128::from(amount),
+            proof: vec![proof.to_string()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(1000, BOB_LEAF)).unwrap_err();
+        assert!(matches!(err, ContractError::StageNotBegun { stage: 1, .. }));
+
+        env.block.height = height + 5;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(1000, BOB_LEAF)).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "gfot".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: "alice".to_string(), amount: Uint128::from(1000u128) }).unwrap(),
+        }));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(1000, BOB_LEAF)).unwrap_err();
+        assert_eq!(err, ContractError::Claimed {});
+        //sent out of the escrow, the curve doesn't move
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!((config.gfot_sent_amount.u128(), config.supply.u128()), (1000, 0));
+
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::BurnUnclaimed { stage: 1 }).unwrap_err();
+        assert_eq!(err, ContractError::StageNotExpired { stage: 1 });
+
+        env.block.height = height + 10;
+        let err = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), claim(2500, ALICE_LEAF)).unwrap_err();
+        assert!(matches!(err, ContractError::StageExpired { stage: 1, .. }));
+
+        let err = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::BurnUnclaimed { stage: 1 }).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::BurnUnclaimed { stage: 1 }).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "gfot".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount: Uint128::from(2500u128) }).unwrap(),
+        }));
+        let stage = query_stage(deps.as_ref(), 1).unwrap();
+        assert_eq!(stage.claimed_amount, stage.total_amount);
+    }
+
+    #[test]
+    fn stages_stop_at_the_last_id() {
+        let mut deps = mock_instance();
+        LATEST_STAGE.save(deps.as_mut().storage, &u8::MAX).unwrap();
+        let register = ExecuteMsg::RegisterAirdrop {
+            merkle_root: ROOT.to_string(),
+            start: None,
+            expiration: None,
+            total_amount: Uint128::zero(),
+            kind: AirdropKind::Curve,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), register).unwrap_err();
+        assert_eq!(err, ContractError::StageLimitReached { max_stage: u8::MAX });
+    }
+
+    #[test]
+    fn referrer_link_sticks() {
+        let mut storage = MockStorage::new();
+        let (a, b, c) = (Addr::unchecked("a"), Addr::unchecked("b"), Addr::unchecked("c"));
+        assert_eq!(resolve_referrer(&mut storage, &b, Some(a.clone())).unwrap(), Some(a.clone()));
+        assert_eq!(resolve_referrer(&mut storage, &b, Some(c.clone())).unwrap(), Some(a.clone()));
+        assert_eq!(resolve_referrer(&mut storage, &a, Some(b.clone())), Err(ContractError::ReferralLoop {}));
+        assert_eq!(REFERRALS.may_load(&storage, &c).unwrap(), None);
+    }
+
+    #[test]
+    fn mint_refunds_what_buys_no_whole_unit() {
+        let mut deps = mock_instance();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint("minter", 25_001, None)).unwrap();
+        assert!(res.attributes.contains(&attr("refund_amount", "5001")));
+        assert_eq!(messages(res), vec![
+            cw20_msg("gfot", Cw20ExecuteMsg::Mint { recipient: "minter".to_string(), amount: Uint128::from(2u128) }),
+            cw20_msg("bfot", Cw20ExecuteMsg::Burn { amount: Uint128::from(20_000u128) }),
+            cw20_msg("bfot", Cw20ExecuteMsg::Transfer { recipient: "minter".to_string(), amount: Uint128::from(5_001u128) }),
+        ]);
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.bfot_burn_amount, Uint128::from(20_000u128));
+        assert_eq!(config.supply, Uint128::from(2u128));
+
+        //too little for a unit comes back whole
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint("minter", 9_999, None)).unwrap();
+        assert_eq!(messages(res), vec![
+            cw20_msg("bfot", Cw20ExecuteMsg::Transfer { recipient: "minter".to_string(), amount: Uint128::from(9_999u128) }),
+        ]);
+    }
+
+    #[test]
+    fn new_gfot_token_is_stored_from_the_reply() {
+        let mut deps = mock_dependencies();
+        let gfot_token = GfotTokenInfo::New {
+            code_id: 9,
+            name: "gFOT".to_string(),
+            symbol: "GFOT".to_string(),
+            decimals: 10,
+            label: "gfot".to_string(),
+        };
+        let msg = InstantiateMsg { gfot_token, ..mock_instantiate_msg() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, INSTANTIATE_GFOT_REPLY_ID);
+        assert!(matches!(
+            &res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Instantiate { code_id: 9, admin: Some(admin), .. }) if admin == "owner"
+        ));
+        //nothing is minted before the reply brings the address
+        assert_eq!(query_config(deps.as_ref()).unwrap().gfot_token_address, None);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint("minter", 20_000, None)).unwrap_err();
+        assert_eq!(err, ContractError::GfotTokenPending {});
+
+        // MsgInstantiateContractResponse with only the contract address set
+        let address = "newgfot";
+        let mut data = vec![0x0a, address.len() as u8];
+        data.extend_from_slice(address.as_bytes());
+        let reply_msg = Reply {
+            id: INSTANTIATE_GFOT_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: Some(Binary::from(data)) }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        assert_eq!(query_config(deps.as_ref()).unwrap().gfot_token_address, Some(address.to_string()));
+
+        let unknown = Reply {
+            id: 7,
+            result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }),
+        };
+        assert_eq!(reply(deps.as_mut(), mock_env(), unknown).unwrap_err(), ContractError::UnknownReplyId { id: 7 });
+    }
+
+    #[test]
+    fn hand_off_freezes_minting() {
+        let mut deps = mock_instance();
+        let hand_off = ExecuteMsg::HandOffMinter { new_minter: "successor".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), hand_off.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), hand_off.clone()).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "gfot".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20MinterMsg::UpdateMinter { new_minter: Some("successor".to_string()) }).unwrap(),
+        }));
+        assert_eq!(query_config(deps.as_ref()).unwrap().successor, Some("successor".to_string()));
+
+        let frozen = ContractError::MintingFrozen { successor: "successor".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint("minter", 20_000, None)).unwrap_err();
+        assert_eq!(err, frozen);
+        let register = ExecuteMsg::RegisterAirdrop {
+            merkle_root: ROOT.to_string(),
+            start: None,
+            expiration: None,
+            total_amount: Uint128::from(100u128),
+            kind: AirdropKind::Gfot,
+        };
+        assert_eq!(execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), register).unwrap_err(), frozen);
+        assert_eq!(execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), hand_off).unwrap_err(), frozen);
+
+        //quotes stop with the mints, the chart stays up
+        let err = query_expected_amount(deps.as_ref(), Uint128::from(20_000u128)).unwrap_err();
+        assert_eq!(err, StdError::generic_err(frozen.to_string()));
+        let chart = query_curve_points(deps.as_ref(), Uint128::zero(), Uint128::from(10u128), 2).unwrap();
+        assert!(chart.frozen);
+        assert_eq!(chart.points.len(), 2);
+    }
+
+    #[test]
+    fn vested_gfot_releases_after_the_cliff() {
+        let vesting = VestingParams { cliff: 100, duration: 1000 };
+        let mut deps = mock_instance_with(InstantiateMsg { vesting: Some(vesting), ..mock_instantiate_msg() });
+        let mut env = mock_env();
+        let start = env.block.time;
+
+        //the 10 gFOT minted are escrowed here
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bfot", &[]), mint("minter", 100_000, None)).unwrap();
+        assert_eq!(res.messages[0].msg, cw20_msg("gfot", Cw20ExecuteMsg::Mint {
+            recipient: env.contract.address.to_string(),
+            amount: Uint128::from(10u128),
+        }));
+
+        let claim = |env: &Env, deps: &mut cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+            execute(deps.as_mut(), env.clone(), mock_info("minter", &[]), ExecuteMsg::ClaimVested {})
+        };
+        env.block.time = start.plus_seconds(99);
+        assert_eq!(claim(&env, &mut deps).unwrap_err(), ContractError::NoVested {});
+
+        //linear from the mint time, not from the cliff
+        env.block.time = start.plus_seconds(400);
+        assert_eq!(query_vesting(deps.as_ref(), env.clone(), "minter".to_string()).unwrap().claimable, Uint128::from(4u128));
+        let res = claim(&env, &mut deps).unwrap();
+        assert_eq!(messages(res), vec![
+            cw20_msg("gfot", Cw20ExecuteMsg::Transfer { recipient: "minter".to_string(), amount: Uint128::from(4u128) }),
+        ]);
+        assert_eq!(claim(&env, &mut deps).unwrap_err(), ContractError::NoVested {});
+
+        env.block.time = start.plus_seconds(1000);
+        let res = claim(&env, &mut deps).unwrap();
+        assert_eq!(messages(res), vec![
+            cw20_msg("gfot", Cw20ExecuteMsg::Transfer { recipient: "minter".to_string(), amount: Uint128::from(6u128) }),
+        ]);
+        assert!(query_vesting(deps.as_ref(), env, "minter".to_string()).unwrap().schedules.is_empty());
+    }
+
+    #[test]
+    fn mint_and_stake_sends_for_the_minter() {
+        let mut deps = mock_instance();
+        let env = mock_env();
+        let stake = ReceiveMsg::MintAndStake { staking_contract: "staking".to_string(), referrer: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bfot", &[]), mint("minter", 30_000, Some(stake.clone()))).unwrap();
+        assert!(res.attributes.contains(&attr("staking_contract", "staking")));
+        assert_eq!(messages(res), vec![
+            cw20_msg("gfot", Cw20ExecuteMsg::Mint { recipient: env.contract.address.to_string(), amount: Uint128::from(3u128) }),
+            cw20_msg("gfot", Cw20ExecuteMsg::Send {
+                contract: "staking".to_string(),
+                amount: Uint128::from(3u128),
+                msg: to_binary(&StakingReceiveMsg::Stake { staker: Some("minter".to_string()) }).unwrap(),
+            }),
+            cw20_msg("bfot", Cw20ExecuteMsg::Burn { amount: Uint128::from(30_000u128) }),
+        ]);
+        //the mint is still the minter's
+        let minters = query_minters(deps.as_ref(), None, None, None).unwrap().minters;
+        assert_eq!(minters[0].address, "minter");
+        assert_eq!(minters[0].gfot_minted, Uint128::from(3u128));
+
+        let vesting = Some(VestingParams { cliff: 0, duration: 100 });
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::UpdateVesting { vesting }).unwrap();
+        let err = execute(deps.as_mut(), env, mock_info("bfot", &[]), mint("minter", 30_000, Some(stake))).unwrap_err();
+        assert_eq!(err, ContractError::StakeWhileVesting {});
+    }
+
+    #[test]
+    fn minters_rank_by_burned() {
+        let mut deps = mock_instance();
+        let burns = [("alice", 30_000), ("bob", 10_000), ("carol", 30_000), ("dave", 20_000), ("erin", 30_000), ("alice", 10_000)];
+        for (minter, amount) in burns {
+            execute(deps.as_mut(), mock_env(), mock_info("bfot", &[]), mint(minter, amount, None)).unwrap();
+        }
+        let page = |start_after: Option<&str>, limit, order_by| {
+            query_minters(deps.as_ref(), start_after.map(|s| s.to_string()), Some(limit), Some(order_by))
+                .unwrap()
+                .minters
+                .into_iter()
+                .map(|m| (m.address, m.bfot_burned.u128()))
+                .collect::<Vec<_>>()
+        };
+        let minter = |address: &str, burned: u128| (address.to_string(), burned);
+
+        //ties on the burned amount go by address, descending too
+        assert_eq!(page(None, 2, MinterOrderBy::Burned), vec![minter("alice", 40_000), minter("erin", 30_000)]);
+        assert_eq!(page(Some("erin"), 2, MinterOrderBy::Burned), vec![minter("carol", 30_000), minter("dave", 20_000)]);
+        assert_eq!(page(Some("dave"), 2, MinterOrderBy::Burned), vec![minter("bob", 10_000)]);
+
+        assert_eq!(page(Some("carol"), 1, MinterOrderBy::Address), vec![minter("dave", 20_000)]);
+        let alice = &query_minters(deps.as_ref(), None, Some(1), None).unwrap().minters[0];
+        assert_eq!((alice.mint_count, alice.gfot_minted), (2, Uint128::from(4u128)));
+    }
+
+    #[test]
+    fn paused_mints_block_curve_claims() {
+        let mut deps = mock_instance();
+        let register = ExecuteMsg::RegisterAirdrop {
+            merkle_root: single_leaf_root("claimer", 20_000),
+            start: None,
+            expiration: None,
+            total_amount: Uint128::from(20_000u128),
+            kind: AirdropKind::Curve,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), register).unwrap();
+
+        let pause = ExecuteMsg::Pause { flags: PauseFlags { mints: true, claims: false } };
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause).unwrap();
+
+        let claim = ExecuteMsg::ClaimAirdrop { stage: 1, amount: Uint128::from(20_000u128), proof: vec![] };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("claimer", &[]), claim.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Paused { action: "mints".to_string() });
+        assert_eq!(query_config(deps.as_ref()).unwrap().supply, Uint128::zero());
+
+        let unpause = ExecuteMsg::Unpause { flags: PauseFlags { mints: true, claims: false } };
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), unpause).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("claimer", &[]), claim).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(query_config(deps.as_ref()).unwrap().supply, Uint128::from(2u128));
+    }
+}
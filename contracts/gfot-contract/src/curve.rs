@@ -0,0 +1,466 @@
+use std::convert::TryFrom;
+
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128, Uint256};
+use integer_sqrt::IntegerSquareRoot;
+
+use crate::msg::{Breakpoint, CurveParams, CurveShape};
+
+/// Price curve of gFOT in bFOT. The supply is counted in gFOT units sold on the curve and
+/// grouped in steps of `step_amount` units, every step has its own bFOT rate.
+pub trait Curve {
+    /// gFOT units sold per step
+    fn step_amount(&self) -> Uint128;
+
+    /// bFOT burned to sell the first `steps` whole steps, strictly increasing.
+    fn steps_cost(&self, steps: u128) -> StdResult<Uint128>;
+
+    /// Largest number of whole steps whose cost fits in `cost`.
+    fn steps_for(&self, cost: Uint128) -> StdResult<u128> {
+        bisect_steps(self, cost)
+    }
+
+    /// bFOT rate of a whole step
+    fn step_rate(&self, step: u128) -> StdResult<Uint128> {
+        Ok(self
+            .steps_cost(step + 1)?
+            .checked_sub(self.steps_cost(step)?)?)
+    }
+
+    /// bFOT burned to mint the curve from zero up to `supply`. The units inside a step
+    /// share the step rate, rounded down.
+    fn cost(&self, supply: Uint128) -> StdResult<Uint128> {
+        let step_amount = self.step_amount();
+        let steps = (supply / step_amount).u128();
+        let rest = supply - Uint128::from(steps) * step_amount;
+
+        let cost = self.steps_cost(steps)?;
+        if rest.is_zero() {
+            return Ok(cost);
+        }
+        let rate = self.step_rate(steps)?;
+        Ok(cost.checked_add(rest.multiply_ratio(rate, step_amount))?)
+    }
+
+    /// Largest supply whose cost fits in `cost`, the exact inverse of `cost`.
+    fn supply_for(&self, cost: Uint128) -> StdResult<Uint128> {
+        let step_amount = self.step_amount();
+        let steps = self.steps_for(cost)?;
+        let left = cost - self.steps_cost(steps)?;
+        let rate = self.step_rate(steps)?;
+
+        // largest rest with rest * rate / step_amount <= left, rounded down
+        let rest = (Uint256::from(left + Uint128::from(1u128)) * Uint256::from(step_amount)
+            - Uint256::one())
+            / Uint256::from(rate);
+        let rest = Uint128::try_from(rest)?;
+
+        Ok(Uint128::from(steps)
+            .checked_mul(step_amount)?
+            .checked_add(rest)?)
+    }
+}
+
+// an overflowing cost never fits
+fn fits<C: Curve + ?Sized>(curve: &C, steps: u128, cost: Uint128) -> bool {
+    matches!(curve.steps_cost(steps), Ok(c) if c <= cost)
+}
+
+fn bisect_steps<C: Curve + ?Sized>(curve: &C, cost: Uint128) -> StdResult<u128> {
+    // grow the upper bound until it costs too much, then bisect
+    let mut lo = 0u128;
+    let mut hi = 1u128;
+    while fits(curve, hi, cost) {
+        lo = hi;
+        hi = hi
+            .checked_mul(2)
+            .ok_or_else(|| StdError::generic_err("curve steps overflow"))?;
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if fits(curve, mid, cost) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(lo)
+}
+
+// n * (n - 1) / 2
+fn triangle(n: u128) -> StdResult<Uint128> {
+    if n == 0 {
+        return Ok(Uint128::zero());
+    }
+    Ok(Uint128::from(n).checked_mul(Uint128::from(n - 1))? / Uint128::from(2u128))
+}
+
+pub fn build_curve(params: &CurveParams) -> Box<dyn Curve> {
+    let start_rate = params.start_rate;
+    let step_amount = params.step_amount;
+    match &params.shape {
+        CurveShape::Linear {} => Box::new(LinearCurve {
+            start_rate,
+            step_amount,
+        }),
+        CurveShape::Exponential { growth } => Box::new(ExponentialCurve {
+            start_rate,
+            step_amount,
+            growth: *growth,
+            max_steps: params.max_steps.unwrap_or(MAX_EXPONENTIAL_STEPS) as u128,
+        }),
+        CurveShape::PiecewiseLinear { breakpoints } => Box::new(PiecewiseLinearCurve {
+            start_rate,
+            step_amount,
+            breakpoints: breakpoints.clone(),
+        }),
+        CurveShape::Sigmoid { max_rate, width } => Box::new(SigmoidCurve {
+            start_rate,
+            max_rate: *max_rate,
+            step_amount,
+            width: *width,
+        }),
+    }
+}
+
+/// Every step costs `step_amount` bFOT more than the previous one, the original gFOT curve.
+pub struct LinearCurve {
+    pub start_rate: Uint128,
+    pub step_amount: Uint128,
+}
+
+impl Curve for LinearCurve {
+    fn step_amount(&self) -> Uint128 {
+        self.step_amount
+    }
+
+    // k * start + step * k * (k - 1) / 2
+    fn steps_cost(&self, steps: u128) -> StdResult<Uint128> {
+        Ok(Uint128::from(steps)
+            .checked_mul(self.start_rate)?
+            .checked_add(triangle(steps)?.checked_mul(self.step_amount)?)?)
+    }
+
+    fn steps_for(&self, cost: Uint128) -> StdResult<u128> {
+        //now use the quadratic equation
+        let a = (cost / self.step_amount).u128();
+        let b = (self.start_rate / self.step_amount).u128();
+        let d = b
+            .checked_mul(2)
+            .and_then(|b2| b2.checked_sub(1))
+            .and_then(|b2| b2.checked_mul(b2))
+            .and_then(|b2| a.checked_mul(8).and_then(|a8| b2.checked_add(a8)));
+        let d = match d {
+            Some(d) => d,
+            None => return bisect_steps(self, cost),
+        };
+        // This n is the floor value when start is a multiple of step, otherwise close to it
+        let mut n = (d.integer_sqrt() + 1).saturating_sub(2 * b) / 2;
+
+        while n > 0 && !fits(self, n, cost) {
+            n -= 1;
+        }
+        while fits(self, n + 1, cost) {
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+// exponential curves are limited to these, the rates are summed one by one
+pub const MIN_EXPONENTIAL_GROWTH: Decimal = Decimal::permille(1);
+pub const MAX_EXPONENTIAL_GROWTH: Decimal = Decimal::percent(100);
+pub const MAX_EXPONENTIAL_STEPS: u64 = 10_000;
+
+/// Every step costs `growth` times more than the previous one, rounded down on every step.
+/// The rates are summed exactly, the steps after `max_steps + 1` are out of the curve.
+pub struct ExponentialCurve {
+    pub start_rate: Uint128,
+    pub step_amount: Uint128,
+    pub growth: Decimal,
+    pub max_steps: u128,
+}
+
+impl ExponentialCurve {
+    // rate of the step after one at `rate`
+    fn grow(&self, rate: Uint128) -> StdResult<Uint128> {
+        Ok(rate.checked_add(rate * self.growth)?)
+    }
+}
+
+impl Curve for ExponentialCurve {
+    fn step_amount(&self) -> Uint128 {
+        self.step_amount
+    }
+
+    fn steps_cost(&self, steps: u128) -> StdResult<Uint128> {
+        if steps > self.max_steps + 1 {
+            return Err(StdError::generic_err("curve steps overflow"));
+        }
+        let mut cost = Uint128::zero();
+        let mut rate = self.start_rate;
+        for step in 0..steps {
+            if step > 0 {
+                rate = self.grow(rate)?;
+            }
+            cost = cost.checked_add(rate)?;
+        }
+        Ok(cost)
+    }
+
+    // one walk over the rates instead of summing them again on every bisection
+    fn steps_for(&self, cost: Uint128) -> StdResult<u128> {
+        let mut total = Uint128::zero();
+        let mut rate = self.start_rate;
+        for step in 0..=self.max_steps {
+            if step > 0 {
+                rate = match self.grow(rate) {
+                    Ok(rate) => rate,
+                    Err(_) => return Ok(step),
+                };
+            }
+            match total.checked_add(rate) {
+                Ok(sum) if sum <= cost => total = sum,
+                _ => return Ok(step),
+            }
+        }
+        Ok(self.max_steps + 1)
+    }
+}
+
+/// Linear pieces, from every breakpoint on the rate grows by the breakpoint increment per step.
+pub struct PiecewiseLinearCurve {
+    pub start_rate: Uint128,
+    pub step_amount: Uint128,
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+impl Curve for PiecewiseLinearCurve {
+    fn step_amount(&self) -> Uint128 {
+        self.step_amount
+    }
+
+    fn steps_cost(&self, steps: u128) -> StdResult<Uint128> {
+        let mut cost = Uint128::zero();
+        let mut rate = self.start_rate;
+        for (i, breakpoint) in self.breakpoints.iter().enumerate() {
+            let from = breakpoint.step as u128;
+            if from >= steps {
+                break;
+            }
+            let to = self
+                .breakpoints
+                .get(i + 1)
+                .map_or(steps, |next| (next.step as u128).min(steps));
+
+            // n * rate + increment * n * (n - 1) / 2 for the steps of this piece
+            let n = to - from;
+            cost = cost
+                .checked_add(Uint128::from(n).checked_mul(rate)?)?
+                .checked_add(triangle(n)?.checked_mul(breakpoint.increment)?)?;
+            rate = rate.checked_add(Uint128::from(n).checked_mul(breakpoint.increment)?)?;
+        }
+        Ok(cost)
+    }
+}
+
+/// S-shaped rise from `start_rate` to `max_rate` over `width` steps, capped at `max_rate` after.
+/// The rate of step i is start + (max - start) * s(i / width) with the smoothstep
+/// s(t) = 3t^2 - 2t^3, whose sums have an exact closed form.
+pub struct SigmoidCurve {
+    pub start_rate: Uint128,
+    pub max_rate: Uint128,
+    pub step_amount: Uint128,
+    pub width: u64,
+}
+
+impl Curve for SigmoidCurve {
+    fn step_amount(&self) -> Uint128 {
+        self.step_amount
+    }
+
+    fn steps_cost(&self, steps: u128) -> StdResult<Uint128> {
+        let w = Uint256::from(self.width as u128);
+        // steps 0..=width are on the ramp, the ones after pay max_rate
+        let ramp = steps.min(self.width as u128 + 1);
+        let capped = steps - ramp;
+
+        // sum over i < ramp of 3 * w * i^2 - 2 * i^3
+        let k = Uint256::from(ramp);
+        let (sum_squares, sum_cubes) = if ramp == 0 {
+            (Uint256::zero(), Uint256::zero())
+        } else {
+            let k1 = k - Uint256::one();
+            let sum_squares = k1.checked_mul(k)?.checked_mul(k + k1)? / Uint256::from(6u128);
+            let triangle = k1.checked_mul(k)? / Uint256::from(2u128);
+            (sum_squares, triangle.checked_mul(triangle)?)
+        };
+        let smooth = Uint256::from(3u128)
+            .checked_mul(w)?
+            .checked_mul(sum_squares)?
+            .checked_sub(Uint256::from(2u128).checked_mul(sum_cubes)?)?;
+        let rise = Uint256::from(self.max_rate - self.start_rate)
+            .checked_mul(smooth)?
+            / w.checked_mul(w)?.checked_mul(w)?;
+
+        Ok(Uint128::from(ramp)
+            .checked_mul(self.start_rate)?
+            .checked_add(Uint128::try_from(rise)?)?
+            .checked_add(Uint128::from(capped).checked_mul(self.max_rate)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u128 = 100_000_000_000_000u128;
+    const STEP: u128 = 10_000_000_000u128;
+
+    fn check_inverse(curve: &dyn Curve, max_cost: u128) {
+        // xorshift64 with a fixed seed, the same 300 costs on every curve
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u128
+        };
+        for _ in 0..300 {
+            let cost = Uint128::from((next() << 64 | next()) % max_cost);
+            let supply = curve.supply_for(cost).unwrap();
+            assert!(curve.cost(supply).unwrap() <= cost);
+            assert!(curve.cost(supply + Uint128::from(1u128)).unwrap() > cost);
+        }
+    }
+
+    #[test]
+    fn linear_matches_reference() {
+        let curve = LinearCurve {
+            start_rate: Uint128::from(START),
+            step_amount: Uint128::from(STEP),
+        };
+        let mut reference = 0u128;
+        for k in 0..2_000u128 {
+            assert_eq!(curve.steps_cost(k).unwrap().u128(), reference);
+            reference += START + k * STEP;
+        }
+        check_inverse(&curve, 1_000_000_000_000_000_000_000u128);
+    }
+
+    #[test]
+    fn linear_inverse_when_start_is_not_a_multiple() {
+        let curve = LinearCurve {
+            start_rate: Uint128::from(1_234_567u128),
+            step_amount: Uint128::from(1_000u128),
+        };
+        check_inverse(&curve, 1_000_000_000_000u128);
+    }
+
+    #[test]
+    fn linear_cost_inside_step_takes_whole_units() {
+        let curve = LinearCurve {
+            start_rate: Uint128::from(START),
+            step_amount: Uint128::from(STEP),
+        };
+        // a unit of the first step costs START / STEP
+        assert_eq!(curve.cost(Uint128::from(3u128)).unwrap().u128(), 3 * START / STEP);
+        assert_eq!(curve.supply_for(Uint128::from(START / STEP * 2 + 1)).unwrap().u128(), 2);
+    }
+
+    #[test]
+    fn exponential_matches_reference() {
+        let curve = ExponentialCurve {
+            start_rate: Uint128::from(START),
+            step_amount: Uint128::from(STEP),
+            growth: Decimal::permille(3),
+            max_steps: 2_000,
+        };
+
+        // every rate is 1.003 times the one before, rounded down
+        let mut rate = START;
+        let mut reference = 0u128;
+        for k in 0..1_500u128 {
+            assert_eq!(curve.steps_cost(k).unwrap().u128(), reference, "step {}", k);
+            reference += rate;
+            rate += rate * 3 / 1000;
+        }
+        check_inverse(&curve, curve.steps_cost(2_000).unwrap().u128());
+    }
+
+    #[test]
+    fn exponential_ends_after_max_steps() {
+        let growths = [MIN_EXPONENTIAL_GROWTH, Decimal::permille(3), MAX_EXPONENTIAL_GROWTH];
+        for growth in growths {
+            let curve = ExponentialCurve {
+                start_rate: Uint128::from(1_000u128),
+                step_amount: Uint128::from(STEP),
+                growth,
+                max_steps: 50,
+            };
+            let mut last = curve.steps_cost(0).unwrap();
+            for k in 1..=51u128 {
+                let cost = curve.steps_cost(k).unwrap();
+                assert!(cost > last, "growth {} step {}", growth, k);
+                // the walk in steps_for agrees with the sums
+                assert_eq!(curve.steps_for(cost).unwrap(), k);
+                assert_eq!(curve.steps_for(cost - Uint128::from(1u128)).unwrap(), k - 1);
+                last = cost;
+            }
+            assert!(curve.steps_cost(52).is_err());
+            assert_eq!(curve.steps_for(Uint128::MAX).unwrap(), 51);
+        }
+    }
+
+    #[test]
+    fn piecewise_matches_reference() {
+        let breakpoints = vec![
+            Breakpoint { step: 0, increment: Uint128::from(STEP) },
+            Breakpoint { step: 100, increment: Uint128::zero() },
+            Breakpoint { step: 250, increment: Uint128::from(5 * STEP) },
+        ];
+        let curve = PiecewiseLinearCurve {
+            start_rate: Uint128::from(START),
+            step_amount: Uint128::from(STEP),
+            breakpoints: breakpoints.clone(),
+        };
+
+        let mut rate = START;
+        let mut reference = 0u128;
+        for k in 0..1_000u128 {
+            assert_eq!(curve.steps_cost(k).unwrap().u128(), reference);
+            reference += rate;
+            let increment = breakpoints
+                .iter()
+                .rev()
+                .find(|b| b.step as u128 <= k)
+                .unwrap()
+                .increment;
+            rate += increment.u128();
+        }
+        check_inverse(&curve, 1_000_000_000_000_000_000_000u128);
+    }
+
+    #[test]
+    fn sigmoid_matches_reference() {
+        let max = 3 * START;
+        let width = 400u128;
+        let curve = SigmoidCurve {
+            start_rate: Uint128::from(START),
+            max_rate: Uint128::from(max),
+            step_amount: Uint128::from(STEP),
+            width: width as u64,
+        };
+
+        // exact sum of the rates times width^3, rounded down once
+        let w3 = width * width * width;
+        let mut reference = 0u128;
+        for k in 0..1_000u128 {
+            assert_eq!(curve.steps_cost(k).unwrap().u128(), reference / w3, "step {}", k);
+            let t = k.min(width);
+            reference += START * w3 + (max - START) * (3 * width * t * t - 2 * t * t * t);
+        }
+        assert_eq!(curve.step_rate(width).unwrap().u128(), max);
+        assert_eq!(curve.step_rate(width + 10).unwrap().u128(), max);
+        check_inverse(&curve, 1_000_000_000_000_000_000_000u128);
+    }
+}
@@ -1,5 +1,5 @@
 use cosmwasm_std::{StdError, Uint128};
-use cw_utils::{Expiration, Scheduled};
+use cw_utils::{Expiration, ParseReplyError, Scheduled};
 use hex::FromHexError;
 use thiserror::Error;
 
@@ -11,9 +11,18 @@ pub enum ContractError {
     #[error("{0}")]
     Hex(#[from] FromHexError),
 
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("No pending ownership proposal")]
+    NoOwnerProposal {},
+
+    #[error("Ownership proposal expired at {expiry}")]
+    OwnerProposalExpired { expiry: Expiration },
+
     #[error("InvalidInput")]
     InvalidInput {},
 
@@ -26,6 +35,30 @@ pub enum ContractError {
     #[error("Not enough bFOT, needs {bfot_accept_amount}")]
     NotEnoughbFOT { bfot_accept_amount:Uint128 },
 
+    #[error("Minting is frozen, gFOT minter moved to {successor}")]
+    MintingFrozen { successor: String },
+
+    #[error("Vesting cliff can't be longer than its duration")]
+    InvalidVesting {},
+
+    #[error("Nothing vested to claim")]
+    NoVested {},
+
+    #[error("Minted gFOT can't be staked while vesting is on")]
+    StakeWhileVesting {},
+
+    #[error("Invalid curve params")]
+    InvalidCurve {},
+
+    #[error("Curve cost overflows by step {max_steps}")]
+    CurveOverflow { max_steps: u64 },
+
+    #[error("No curve params proposed")]
+    NoPendingCurve {},
+
+    #[error("Curve params can't be applied before {effective_at}")]
+    CurveTimelocked { effective_at: u64 },
+
     #[error("Already claimed")]
     Claimed {},
 
@@ -35,6 +68,12 @@ pub enum ContractError {
     #[error("Verification failed")]
     VerificationFailed {},
 
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("The gFOT token is not instantiated yet")]
+    GfotTokenPending {},
+
     #[error("Cannot migrate from different contract type: {previous_contract}")]
     CannotMigrate { previous_contract: String },
 
@@ -43,4 +82,34 @@ pub enum ContractError {
 
     #[error("Airdrop stage {stage} begins at {start}")]
     StageNotBegun { stage: u8, start: Scheduled },
+
+    #[error("Airdrop stage {stage} has not expired yet")]
+    StageNotExpired { stage: u8 },
+
+    #[error("Airdrop stage {stage} would exceed its total amount")]
+    StageAmountExceeded { stage: u8 },
+
+    #[error("No airdrop stages left, the last one is {max_stage}")]
+    StageLimitReached { max_stage: u8 },
+
+    #[error("Fee is over the maximum of {max_bps} bps")]
+    FeeTooHigh { max_bps: u64 },
+
+    #[error("No fees to withdraw")]
+    NoFees {},
+
+    #[error("{action} paused by the guardian")]
+    Paused { action: String },
+
+    #[error("Referral share is over the maximum of {max_bps} bps")]
+    ReferralTooHigh { max_bps: u64 },
+
+    #[error("Cannot refer yourself")]
+    SelfReferral {},
+
+    #[error("Referral loop")]
+    ReferralLoop {},
+
+    #[error("Referral chain is longer than {max_depth}")]
+    ReferralChainTooDeep { max_depth: u32 },
 }
@@ -1,25 +1,205 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cw20::{Cw20ReceiveMsg};
-use cosmwasm_std::{Uint128, Addr};
+use cosmwasm_std::{Uint128, Addr, Decimal, Decimal256};
+use cw_utils::{Expiration, Scheduled};
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InstantiateMsg {
     /// Owner if none set to info.sender.
     pub owner: Option<String>,
     pub bfot_token_address: Addr,
+    pub gfot_token: GfotTokenInfo,
+    pub vesting: Option<VestingParams>,
+    /// Curve params if none set to the original linear curve, 10000 bFOT start and 1 bFOT step.
+    pub curve: Option<CurveParams>,
+    /// No fee if not set.
+    pub fee: Option<FeeConfig>,
+    /// Can pause and unpause besides the owner, none if not set.
+    pub guardian: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeConfig {
+    /// Share of the minted gFOT taken as fee, in basis points
+    pub bps: u64,
+    /// Where the fee goes. If not set it's kept here until the owner sends it out with
+    /// WithdrawFees, nothing passes it on to stakers.
+    pub treasury: Option<String>,
+    /// Share of the fee minted to the minter's referrer, in basis points of the fee
+    #[serde(default)]
+    pub referral_bps: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurveParams {
+    /// bFOT price of the first STEP of gFOT
+    pub start_rate: Uint128,
+    /// gFOT sold per step, all units of a step share one rate
+    pub step_amount: Uint128,
+    /// Number of steps that can be minted, unlimited if none set.
+    pub max_steps: Option<u64>,
+    /// How the rate grows from step to step, linear if none set.
+    #[serde(default)]
+    pub shape: CurveShape,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveShape {
+    /// Each step costs step_amount bFOT more than the previous one.
+    Linear {},
+    /// Each step costs `growth` times more than the previous one, rounded down. Growth must be
+    /// within 0.1% and 100%, grow the start rate by at least 1 and max_steps be set to at most
+    /// 10000 with the cost of the step after the last fitting in 128 bits.
+    Exponential { growth: Decimal },
+    /// From each breakpoint on the rate grows by its increment per step.
+    /// The first breakpoint must be at step 0.
+    PiecewiseLinear { breakpoints: Vec<Breakpoint> },
+    /// S-shaped rise from start_rate to max_rate over `width` steps, flat at max_rate after.
+    Sigmoid { max_rate: Uint128, width: u64 },
+}
+
+impl Default for CurveShape {
+    fn default() -> Self {
+        CurveShape::Linear {}
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Breakpoint {
+    pub step: u64,
+    pub increment: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCurveParams {
+    pub curve: CurveParams,
+    /// Seconds, the proposal can be applied from this time on
+    pub effective_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingParams {
+    /// Seconds after a mint before anything can be claimed.
+    pub cliff: u64,
+    /// Seconds after a mint until it is fully released, linearly from the mint time.
+    pub duration: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GfotTokenInfo {
+    /// Use an already deployed gFOT cw20, this contract must be its minter.
+    Existing {
+        address: String,
+    },
+    /// Instantiate a new cw20-base gFOT with this contract as minter.
+    New {
+        /// cw20-base 0.16 or later, older ones can't hand off the minter.
+        code_id: u64,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        label: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     UpdateConfig {
-        /// NewOwner if non sent, contract gets locked. Recipients can receive airdrops
-        /// but owner cannot register new stages.
-        new_owner: Option<String>,
         gfot_token_address: Option<Addr>
     },
+    /// Offers ownership to the address, it takes over once it accepts
+    ProposeOwner {
+        address: String,
+        /// Proposal never expires if not set
+        expiry: Option<Expiration>,
+    },
+    AcceptOwnership {},
+    /// Drops the pending proposal
+    CancelProposal {},
+    /// Leaves the contract without an owner for good
+    RenounceOwnership {},
     Receive(Cw20ReceiveMsg),
+    /// Moves the gFOT minter role to the successor contract and stops minting here.
+    /// Needs a gFOT token on cw20-base 0.16 or later, the first with UpdateMinter.
+    HandOffMinter {
+        new_minter: String,
+    },
+    /// Registers a new airdrop stage. For `Gfot` stages the total amount is minted
+    /// into this contract up front and paid out on claim.
+    RegisterAirdrop {
+        /// MerkleRoot is hex-encoded merkle root.
+        merkle_root: String,
+        start: Option<Scheduled>,
+        expiration: Option<Expiration>,
+        total_amount: Uint128,
+        kind: AirdropKind,
+    },
+    /// Claims the amount of the leaf `sha256(address + amount)` in the stage.
+    ClaimAirdrop {
+        stage: u8,
+        amount: Uint128,
+        /// Proof is hex-encoded merkle proof.
+        proof: Vec<String>,
+    },
+    /// Burns the gFOT left unclaimed in an expired stage.
+    BurnUnclaimed {
+        stage: u8,
+    },
+    /// Turns vesting of minted gFOT on with the given params, or off with None.
+    /// Schedules already created keep their own params.
+    UpdateVesting {
+        vesting: Option<VestingParams>,
+    },
+    ClaimVested {},
+    /// Proposes new curve params, they can be applied once the timelock has passed.
+    ProposeCurveParams {
+        curve: CurveParams,
+    },
+    ApplyCurveParams {},
+    CancelCurveParams {},
+    UpdateFee {
+        fee: FeeConfig,
+    },
+    /// Owner only, sends the kept fees to the recipient, the owner if not set
+    WithdrawFees {
+        recipient: Option<String>,
+    },
+    /// Owner only, removes the guardian if not set
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Halts the flagged actions, guardian or owner only
+    Pause {
+        flags: PauseFlags,
+    },
+    /// Resumes the flagged actions, guardian or owner only
+    Unpause {
+        flags: PauseFlags,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AirdropKind {
+    /// Leaf amount is gFOT, sent from the amount escrowed at registration.
+    Gfot,
+    /// Leaf amount is a bFOT credit spent on the curve for free, moving the curve position
+    /// like a burn of the same amount would.
+    Curve,
+}
+
+/// cw20-base `UpdateMinter`, added in cw20-base 0.16. This contract builds against 0.11,
+/// which doesn't have it, so the gFOT token has to run 0.16 or later to hand off.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20MinterMsg {
+    UpdateMinter {
+        new_minter: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -27,12 +207,62 @@ pub enum ExecuteMsg {
 pub enum ReceiveMsg {
     Fot {},
     Bfot {},
+    /// Burns the bFOT for gFOT. The referrer is set once per minter, later mints pay the same one
+    /// and ignore the one passed.
+    Mint {
+        referrer: Option<String>,
+    },
+    /// Burns the bFOT and stakes the minted gFOT in the staking contract for the sender.
+    MintAndStake {
+        staking_contract: String,
+        referrer: Option<String>,
+    },
+}
+
+/// gfotstaking `ReceiveMsg`, sent along with the minted gFOT on MintAndStake.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingReceiveMsg {
+    Stake {
+        staker: Option<String>,
+    },
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
-    ExpectedAmount {bfot_amount: Uint128}
+    /// Guardian and the paused actions
+    Status {},
+    /// Fails once the minter is handed off
+    ExpectedAmount {bfot_amount: Uint128},
+    /// Samples `points` positions of the curve between the two gFOT supplies, both included.
+    /// Still answers once the minter is handed off, flagged as frozen.
+    CurvePoints {
+        from_supply: Uint128,
+        to_supply: Uint128,
+        points: u32
+    },
+    LatestStage {},
+    Stage { stage: u8 },
+    IsClaimed { stage: u8, address: String },
+    Vesting { address: String },
+    /// Per-address mint history, by address or as a leaderboard by bFOT burned
+    Minters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order_by: Option<MinterOrderBy>
+    },
+    /// Minters referred and gFOT earned by a referrer
+    Referrals { referrer: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MinterOrderBy {
+    /// Ascending by address, the default
+    Address,
+    /// Descending by bFOT burned, ties descending by address
+    Burned
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -40,12 +270,26 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     pub owner: Option<String>,
     pub bfot_token_address: String,
-    pub gfot_token_address: String,
+    /// None while the new gFOT token is being instantiated
+    pub gfot_token_address: Option<String>,
     pub bfot_burn_amount: Uint128,
+    /// gFOT minted on the curve, fees included, and claimed from airdrops of either kind
     pub gfot_sent_amount: Uint128,
     pub bfot_expected_amount: Uint128,
     pub rate: Uint128,
-    pub left: Uint128
+    pub left: Uint128,
+    /// gFOT sold on the curve so far, the position the price is taken from
+    pub supply: Uint128,
+    pub successor: Option<String>,
+    pub vesting: Option<VestingParams>,
+    pub curve: CurveParams,
+    pub pending_curve: Option<PendingCurveParams>,
+    pub fee_bps: u64,
+    pub fee_treasury: Option<String>,
+    /// Fees kept here, not yet withdrawn
+    pub fee_kept: Uint128,
+    pub fee_referral_bps: u64,
+    pub pending_owner: Option<OwnerProposal>
 
 }
 
@@ -53,9 +297,130 @@ pub struct ConfigResponse {
 #[serde(rename_all = "snake_case")]
 pub struct ExpectedAmountResponse {
     pub bfot_burn_amount: Uint128,
-    pub gfot_send_amount: Uint128
+    /// gFOT received after the fee
+    pub gfot_send_amount: Uint128,
+    pub fee_amount: Uint128,
+    /// bFOT that can't buy a whole gFOT unit, sent back to the sender
+    pub refund_amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ReferralsResponse {
+    pub referrer: String,
+    pub referred_count: u64,
+    pub reward_amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CurvePoint {
+    /// gFOT minted along the curve
+    pub supply: Uint128,
+    /// bFOT paid per gFOT unit at this supply, the step rate over step_amount
+    pub price: Decimal256,
+    /// bFOT paid for the whole step at this supply
+    pub step_cost: Uint128,
+    /// bFOT burned to mint the curve from zero up to this supply
+    pub cost: Uint128
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CurvePointsResponse {
+    pub points: Vec<CurvePoint>,
+    pub current: CurvePoint,
+    /// Minting moved to a successor, the curve no longer moves
+    pub frozen: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct LatestStageResponse {
+    pub latest_stage: u8
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct StageResponse {
+    pub stage: u8,
+    /// MerkleRoot is hex-encoded merkle root.
+    pub merkle_root: String,
+    pub start: Option<Scheduled>,
+    pub expiration: Option<Expiration>,
+    pub kind: AirdropKind,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct IsClaimedResponse {
+    pub is_claimed: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct VestingSchedule {
+    pub amount: Uint128,
+    pub claimed: Uint128,
+    /// Mint time in seconds
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct VestingResponse {
+    pub address: String,
+    pub schedules: Vec<VestingSchedule>,
+    /// Amount ClaimVested would send right now
+    pub claimable: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct MinterInfoResponse {
+    pub address: String,
+    pub bfot_burned: Uint128,
+    pub gfot_minted: Uint128,
+    pub mint_count: u64,
+    pub first_mint: u64,
+    pub last_mint: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct MintersResponse {
+    pub minters: Vec<MinterInfoResponse>
+}
+
+
+/// Actions the guardian can halt. Flags not set are left alone by Pause and Unpause.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseFlags {
+    /// bFOT burns for gFOT, MintAndStake included
+    #[serde(default)]
+    pub mints: bool,
+    /// Airdrop and vested gFOT claims
+    #[serde(default)]
+    pub claims: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct StatusResponse {
+    pub guardian: Option<String>,
+    pub paused: PauseFlags
+}
+
+/// Owner proposed by the current one, waiting to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerProposal {
+    pub owner: Addr,
+    pub expiry: Expiration
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -2,7 +2,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Expiration, Scheduled};
+
+use crate::msg::{AirdropKind, CurveParams, OwnerProposal, PauseFlags, PendingCurveParams, VestingParams, VestingSchedule};
 
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -10,12 +13,143 @@ pub struct Config {
     /// Owner If None set, contract is frozen.
     pub owner: Option<Addr>,
     pub bfot_token_address: Addr,
-    pub gfot_token_address: Addr,
+    /// None until the reply of the gFOT instantiation stores it
+    pub gfot_token_address: Option<Addr>,
     pub bfot_burn_amount: Uint128,
+    /// gFOT minted on the curve and claimed from airdrops, unlike supply it counts gFOT stages
     pub gfot_sent_amount: Uint128,
+    /// bFOT rate of the current step
     pub rate: Uint128,
-    pub left: Uint128
+    /// bFOT left until the current step is sold
+    pub left: Uint128,
+    /// gFOT sold on the curve, the position the price is taken from
+    pub supply: Uint128,
+    /// Contract the gFOT minter role was handed off to. If set, minting is frozen.
+    pub successor: Option<Addr>,
+    /// If set, minted gFOT is escrowed here and released by ClaimVested.
+    pub vesting: Option<VestingParams>,
+    pub curve: CurveParams,
+    /// Fee on the minted gFOT, in basis points
+    pub fee_bps: u64,
+    /// Fee recipient, fees are minted here and counted in fee_kept if None
+    pub fee_treasury: Option<Addr>,
+    /// gFOT fees held here until the owner withdraws them
+    pub fee_kept: Uint128,
+    /// Share of the fee minted to referrers, in basis points of the fee
+    pub fee_referral_bps: u64
 }
 
 pub const CONFIG_KEY: &str = "config";
 pub const CONFIG: Item<Config> = Item::new(CONFIG_KEY);
+
+/// Config as stored by any earlier version, read by migrate. Fields added later are optional.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV0 {
+    pub owner: Option<Addr>,
+    pub bfot_token_address: Addr,
+    pub gfot_token_address: Addr,
+    pub bfot_burn_amount: Uint128,
+    pub gfot_sent_amount: Uint128,
+    pub rate: Uint128,
+    pub left: Uint128,
+    pub successor: Option<Addr>,
+    pub vesting: Option<VestingParams>,
+    pub curve: Option<CurveParams>,
+    pub supply: Option<Uint128>,
+    pub fee_bps: Option<u64>,
+    pub fee_treasury: Option<Addr>,
+    pub fee_kept: Option<Uint128>,
+    pub fee_referral_bps: Option<u64>
+}
+
+pub const CONFIG_V0: Item<ConfigV0> = Item::new(CONFIG_KEY);
+
+pub const PENDING_CURVE_KEY: &str = "pending_curve";
+pub const PENDING_CURVE: Item<PendingCurveParams> = Item::new(PENDING_CURVE_KEY);
+
+pub const OWNER_PROPOSAL_KEY: &str = "owner_proposal";
+pub const OWNER_PROPOSAL: Item<OwnerProposal> = Item::new(OWNER_PROPOSAL_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseState {
+    /// Can pause and unpause besides the owner
+    pub guardian: Option<Addr>,
+    pub paused: PauseFlags
+}
+
+pub const PAUSE_KEY: &str = "pause";
+/// Missing on deployments from before pausing, read as nothing paused
+pub const PAUSE: Item<PauseState> = Item::new(PAUSE_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Stage {
+    pub merkle_root: String,
+    pub start: Option<Scheduled>,
+    pub expiration: Option<Expiration>,
+    pub kind: AirdropKind,
+    pub total_amount: Uint128,
+    /// Amount claimed so far. Set to total_amount once the unclaimed rest is burned.
+    pub claimed_amount: Uint128
+}
+
+pub const LATEST_STAGE_KEY: &str = "latest_stage";
+pub const LATEST_STAGE: Item<u8> = Item::new(LATEST_STAGE_KEY);
+
+pub const STAGES_KEY: &str = "stages";
+pub const STAGES: Map<u8, Stage> = Map::new(STAGES_KEY);
+
+pub const CLAIMS_KEY: &str = "claims";
+pub const CLAIMS: Map<(u8, &Addr), bool> = Map::new(CLAIMS_KEY);
+
+pub const VESTING_KEY: &str = "vesting";
+pub const VESTING: Map<&Addr, Vec<VestingSchedule>> = Map::new(VESTING_KEY);
+
+/// Per-address totals of the bFOT burned for gFOT
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterInfo {
+    pub bfot_burned: Uint128,
+    pub gfot_minted: Uint128,
+    pub mint_count: u64,
+    /// Block time of the first and latest mint, in seconds
+    pub first_mint: u64,
+    pub last_mint: u64
+}
+
+pub struct MinterIndexes<'a> {
+    // big endian bfot_burned, so the index iterates in burned order
+    pub burned: MultiIndex<'a, Vec<u8>, MinterInfo, Addr>,
+}
+
+impl<'a> IndexList<MinterInfo> for MinterIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<MinterInfo>> + '_> {
+        let v: Vec<&dyn Index<MinterInfo>> = vec![&self.burned];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const MINTERS_KEY: &str = "minters";
+
+pub fn minters<'a>() -> IndexedMap<'a, &'a Addr, MinterInfo, MinterIndexes<'a>> {
+    let indexes = MinterIndexes {
+        burned: MultiIndex::new(
+            |m: &MinterInfo| m.bfot_burned.u128().to_be_bytes().to_vec(),
+            MINTERS_KEY,
+            "minters__burned",
+        ),
+    };
+    IndexedMap::new(MINTERS_KEY, indexes)
+}
+
+pub const REFERRERS_KEY: &str = "referrers";
+/// Referrer of each referred minter, set on their first referred mint
+pub const REFERRERS: Map<&Addr, Addr> = Map::new(REFERRERS_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReferralInfo {
+    pub referred_count: u64,
+    /// gFOT minted to the referrer out of fees
+    pub reward_amount: Uint128
+}
+
+pub const REFERRALS_KEY: &str = "referrals";
+pub const REFERRALS: Map<&Addr, ReferralInfo> = Map::new(REFERRALS_KEY);
@@ -9,14 +9,15 @@ use cosmwasm_std::{
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Cw20QueryMsg, Cw20CoinVerified};
 use cw20::{TokenInfoResponse, Balance};
-use cw_utils::{maybe_addr};
+use cw_utils::{maybe_addr, Expiration};
 use cw_storage_plus::Bound;
 use crate::error::ContractError;
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveMsg, StakerListResponse, StakerInfo, CountInfo, StakerResponse
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveMsg, StakerListResponse, StakerInfo, CountInfo, StakerResponse,
+    PauseFlags, StatusResponse, OwnerProposal
 };
 use crate::state::{
-    Config, CONFIG, STAKERS
+    Config, CONFIG, STAKERS, PauseState, PAUSE, OWNER_PROPOSAL
 };
 
 // Version info, for migration info
@@ -51,6 +52,8 @@ pub fn instantiate(
         apy_prefix: msg.apy_prefix
     };
     CONFIG.save(deps.storage, &config)?;
+    let guardian = msg.guardian.map(|g| deps.api.addr_validate(&g)).transpose()?;
+    PAUSE.save(deps.storage, &PauseState { guardian, paused: PauseFlags::default() })?;
 
     Ok(Response::default())
 }
@@ -63,17 +66,24 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { new_owner } => execute_update_config(deps, info, new_owner),
+        ExecuteMsg::ProposeOwner { address, expiry } => execute_propose_owner(deps, env, info, address, expiry),
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelProposal {} => execute_cancel_proposal(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
         ExecuteMsg::UpdateConstants { daily_fot_amount, apy_prefix } => execute_update_constants(deps, info, daily_fot_amount, apy_prefix),
         ExecuteMsg::Receive(msg) => try_receive(deps, env, info, msg),
         ExecuteMsg::WithdrawFot {} => try_withdraw_fot(deps, env, info),
         ExecuteMsg::WithdrawGFot {} => try_withdraw_gfot(deps, env, info),
         ExecuteMsg::ClaimReward {} => try_claim_reward(deps, env, info),
         ExecuteMsg::Unstake {} => try_unstake(deps, env, info),
+        ExecuteMsg::EmergencyUnstake {} => try_emergency_unstake(deps, info),
         ExecuteMsg::UpdateLastTime { last_time } => execute_update_last_time(deps, info, last_time),
         ExecuteMsg::AddStakers { stakers } => execute_add_stakers(deps, info, stakers),
         ExecuteMsg::RemoveStaker { address } => execute_remove_staker(deps, info, address),
         ExecuteMsg::RemoveAllStakers { start_after, limit } => execute_remove_all_stakers(deps, info, start_after, limit),
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::Pause { flags } => execute_set_paused(deps, info, flags, true),
+        ExecuteMsg::Unpause { flags } => execute_set_paused(deps, info, flags, false),
     }
 }
 
@@ -146,6 +156,18 @@ pub fn try_receive(
 
     // Staking case
     if info.sender == cfg.gfot_token_address {
+        if PAUSE.may_load(deps.storage)?.unwrap_or_default().paused.stakes {
+            return Err(ContractError::Paused { action: "stakes".to_string() })
+        }
+        // a contract can stake the gFOT it sends on behalf of someone else
+        let mut staker = None;
+        if !wrapper.msg.is_empty() {
+            if let ReceiveMsg::Stake { staker: Some(address) } = from_binary(&wrapper.msg)? {
+                staker = Some(deps.api.addr_validate(&address)?);
+            }
+        }
+        let user_addr = &staker.unwrap_or_else(|| user_addr.clone());
+
         update_total_reward(deps.storage, deps.api, env, None)?;
         cfg = CONFIG.load(deps.storage)?;
         let exists = STAKERS.may_load(deps.storage, user_addr.clone())?;
@@ -192,6 +214,9 @@ pub fn try_claim_reward(
     info: MessageInfo
 ) -> Result<Response, ContractError> {
 
+    if PAUSE.may_load(deps.storage)?.unwrap_or_default().paused.claims {
+        return Err(ContractError::Paused { action: "claims".to_string() })
+    }
     update_total_reward(deps.storage, deps.api, env, None)?;
     let mut cfg = CONFIG.load(deps.storage)?;
 
@@ -278,6 +303,43 @@ pub fn try_unstake(
         ]));
 }
 
+//skips update_total_reward so a broken reward split can't lock the principal
+pub fn try_emergency_unstake(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let (amount, reward) = STAKERS.may_load(deps.storage, info.sender.clone())?.unwrap_or_default();
+
+    if amount == Uint128::zero() {
+        return Err(ContractError::NoStaked {});
+    }
+
+    //the forfeited reward stays in fot_amount for the other stakers
+    cfg.gfot_amount = cfg.gfot_amount.saturating_sub(amount);
+    CONFIG.save(deps.storage, &cfg)?;
+    STAKERS.remove(deps.storage, info.sender.clone());
+
+    let exec_cw20_transfer = WasmMsg::Execute {
+        contract_addr: cfg.gfot_token_address.clone().into(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.clone().into(),
+            amount,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(exec_cw20_transfer)
+        .add_attributes(vec![
+            attr("action", "emergency_unstake"),
+            attr("address", info.sender),
+            attr("gfot_amount", amount),
+            attr("forfeited_reward", reward),
+        ]))
+}
+
 pub fn check_owner(
     deps: &DepsMut,
     info: &MessageInfo
@@ -290,28 +352,130 @@ pub fn check_owner(
     Ok(Response::new().add_attribute("action", "check_owner"))
 }
 
-pub fn execute_update_config(
+//stakes and claims can be halted by the guardian as well as the owner
+pub fn check_guardian(
+    deps: &DepsMut,
+    info: &MessageInfo
+) -> Result<PauseState, ContractError> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    if pause.guardian.as_ref() != Some(&info.sender) {
+        check_owner(deps, info)?;
+    }
+    Ok(pause)
+}
+
+pub fn execute_update_guardian(
     deps: DepsMut,
     info: MessageInfo,
-    new_owner: Option<String>,
+    guardian: Option<String>
 ) -> Result<Response, ContractError> {
-    // authorize owner
     check_owner(&deps, &info)?;
-    
-    //test code for checking if check_owner works well
-    // return Err(ContractError::InvalidInput {});
-    // if owner some validated to addr, otherwise set to none
-    let mut tmp_owner = None;
-    if let Some(addr) = new_owner {
-        tmp_owner = Some(deps.api.addr_validate(&addr)?)
+
+    let guardian = guardian.map(|g| deps.api.addr_validate(&g)).transpose()?;
+    let mut pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    pause.guardian = guardian;
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new().add_attribute("action", "update_guardian"))
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    flags: PauseFlags,
+    paused: bool
+) -> Result<Response, ContractError> {
+    let mut pause = check_guardian(&deps, &info)?;
+    if flags.stakes {
+        pause.paused.stakes = paused;
+    }
+    if flags.claims {
+        pause.paused.claims = paused;
+    }
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", if paused { "pause" } else { "unpause" }),
+        attr("stakes", pause.paused.stakes.to_string()),
+        attr("claims", pause.paused.claims.to_string()),
+    ]))
+}
+
+pub fn execute_propose_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expiry: Option<Expiration>
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    let owner = deps.api.addr_validate(&address)?;
+    let expiry = expiry.unwrap_or_default();
+    if expiry.is_expired(&env.block) {
+        return Err(ContractError::OwnerProposalExpired { expiry })
+    }
+    OWNER_PROPOSAL.save(deps.storage, &OwnerProposal { owner: owner.clone(), expiry })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", owner),
+        attr("expiry", expiry.to_string()),
+    ]))
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let proposal = OWNER_PROPOSAL.may_load(deps.storage)?.ok_or(ContractError::NoOwnerProposal {})?;
+    if info.sender != proposal.owner {
+        return Err(ContractError::Unauthorized {})
+    }
+    if proposal.expiry.is_expired(&env.block) {
+        return Err(ContractError::OwnerProposalExpired { expiry: proposal.expiry })
     }
 
     CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
-        exists.owner = tmp_owner;
+        exists.owner = Some(proposal.owner);
         Ok(exists)
     })?;
+    OWNER_PROPOSAL.remove(deps.storage);
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender),
+    ]))
+}
+
+pub fn execute_cancel_proposal(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    if OWNER_PROPOSAL.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoOwnerProposal {})
+    }
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_proposal"))
+}
+
+pub fn execute_renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+
+    CONFIG.update(deps.storage, |mut exists| -> StdResult<_> {
+        exists.owner = None;
+        Ok(exists)
+    })?;
+    OWNER_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
 }
 
 
@@ -483,6 +647,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} 
             => to_binary(&query_config(deps)?),
+        QueryMsg::Status {} => to_binary(&query_status(deps)?),
         QueryMsg::Staker {address} 
             => to_binary(&query_staker(deps, address)?),
         QueryMsg::ListStakers {start_after, limit} 
@@ -492,6 +657,14 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     }
 }
 
+pub fn query_status(deps: Deps) -> StdResult<StatusResponse> {
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    Ok(StatusResponse {
+        guardian: pause.guardian.map(|g| g.into()),
+        paused: pause.paused
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let cfg = CONFIG.load(deps.storage)?;
     Ok(ConfigResponse {
@@ -503,7 +676,8 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         gfot_amount: cfg.gfot_amount,
         last_time: cfg.last_time,
         daily_fot_amount: cfg.daily_fot_amount,
-        apy_prefix: cfg.apy_prefix
+        apy_prefix: cfg.apy_prefix,
+        pending_owner: OWNER_PROPOSAL.may_load(deps.storage)?
     })
 }
 
@@ -608,3 +782,148 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
     Ok(Response::default())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+
+    fn mock_instance(daily_fot_amount: u128) -> cosmwasm_std::OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), InstantiateMsg {
+            owner: None,
+            fot_token_address: Addr::unchecked("fot"),
+            bfot_token_address: Addr::unchecked("bfot"),
+            gfot_token_address: Addr::unchecked("gfot"),
+            daily_fot_amount: Uint128::from(daily_fot_amount),
+            apy_prefix: Uint128::zero(),
+            guardian: Some("guardian".to_string()),
+        }).unwrap();
+        deps
+    }
+
+    fn receive(token: &str, sender: &str, amount: u128) -> (MessageInfo, ExecuteMsg) {
+        (mock_info(token, &[]), ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(amount),
+            msg: Binary::default(),
+        }))
+    }
+
+    fn gfot_transfer(recipient: &str, amount: u128) -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "gfot".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: recipient.to_string(), amount: Uint128::from(amount) }).unwrap(),
+            funds: vec![],
+        })
+    }
+
+    #[test]
+    fn guardian_pauses_stakes() {
+        let mut deps = mock_instance(1000);
+
+        let pause = ExecuteMsg::Pause { flags: PauseFlags { stakes: true, claims: false } };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), pause.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause).unwrap();
+        let status = query_status(deps.as_ref()).unwrap();
+        assert!(status.paused.stakes && !status.paused.claims);
+
+        let (info, msg) = receive("gfot", "alice", 100);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::Paused { action: "stakes".to_string() });
+        //funding the rewards isn't a stake
+        let (info, msg) = receive("fot", "funder", 100);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let unpause = ExecuteMsg::Unpause { flags: PauseFlags { stakes: true, claims: false } };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), unpause).unwrap();
+        assert!(!query_status(deps.as_ref()).unwrap().paused.stakes);
+        let (info, msg) = receive("gfot", "alice", 100);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn ownership_moves_on_accept() {
+        let mut deps = mock_instance(1000);
+        let env = mock_env();
+        let propose = ExecuteMsg::ProposeOwner { address: "next".to_string(), expiry: None };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), propose.clone()).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecuteMsg::CancelProposal {}).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("next", &[]), ExecuteMsg::AcceptOwnership {}).unwrap_err();
+        assert_eq!(err, ContractError::NoOwnerProposal {});
+
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), propose).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("next", &[]), ExecuteMsg::AcceptOwnership {}).unwrap();
+        let update = ExecuteMsg::UpdateConstants { daily_fot_amount: Uint128::from(5u128), apy_prefix: Uint128::zero() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), update.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), mock_info("next", &[]), update).unwrap();
+
+        //renouncing drops a pending proposal as well
+        let propose = ExecuteMsg::ProposeOwner { address: "later".to_string(), expiry: None };
+        execute(deps.as_mut(), env.clone(), mock_info("next", &[]), propose).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("next", &[]), ExecuteMsg::RenounceOwnership {}).unwrap();
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!((config.owner, config.pending_owner), (None, None));
+        let err = execute(deps.as_mut(), env, mock_info("later", &[]), ExecuteMsg::AcceptOwnership {}).unwrap_err();
+        assert_eq!(err, ContractError::NoOwnerProposal {});
+    }
+
+    #[test]
+    fn emergency_unstake_when_rewards_overflow() {
+        let mut deps = mock_instance(u128::MAX);
+        let mut env = mock_env();
+        let (info, msg) = receive("gfot", "alice", 2);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        //a day of u128::MAX split over two gFOT overflows in update_total_reward
+        env.block.time = env.block.time.plus_seconds(86400);
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        let unstake = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::Unstake {})
+        }));
+        std::panic::set_hook(hook);
+        assert!(unstake.is_err());
+
+        let res = execute(deps.as_mut(), env, mock_info("alice", &[]), ExecuteMsg::EmergencyUnstake {}).unwrap();
+        assert_eq!(res.messages[0].msg, gfot_transfer("alice", 2));
+        assert_eq!(STAKERS.may_load(deps.as_ref().storage, Addr::unchecked("alice")).unwrap(), None);
+        assert_eq!(query_config(deps.as_ref()).unwrap().gfot_amount, Uint128::zero());
+    }
+
+    #[test]
+    fn emergency_unstake_while_paused_forfeits_reward() {
+        let mut deps = mock_instance(1000);
+        let mut env = mock_env();
+        for (token, sender, amount) in [("fot", "funder", 5000), ("gfot", "alice", 300), ("gfot", "bob", 100)] {
+            let (info, msg) = receive(token, sender, amount);
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        //bob's second stake settles the first day, 750 to alice and 250 to bob
+        env.block.time = env.block.time.plus_seconds(86400);
+        let (info, msg) = receive("gfot", "bob", 100);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(query_staker(deps.as_ref(), Addr::unchecked("alice")).unwrap().reward, Uint128::from(750u128));
+
+        let pause = ExecuteMsg::Pause { flags: PauseFlags { stakes: true, claims: true } };
+        execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), pause).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::ClaimReward {}).unwrap_err();
+        assert_eq!(err, ContractError::Paused { action: "claims".to_string() });
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::EmergencyUnstake {}).unwrap();
+        assert_eq!(res.messages[0].msg, gfot_transfer("alice", 300));
+        assert!(res.attributes.contains(&attr("forfeited_reward", "750")));
+
+        assert_eq!(STAKERS.may_load(deps.as_ref().storage, Addr::unchecked("alice")).unwrap(), None);
+        let bob = STAKERS.load(deps.as_ref().storage, Addr::unchecked("bob")).unwrap();
+        assert_eq!(bob, (Uint128::from(200u128), Uint128::from(250u128)));
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.gfot_amount, Uint128::from(200u128));
+        // the forfeited reward stays in the pool
+        assert_eq!(config.fot_amount, Uint128::from(5000u128));
+
+        let err = execute(deps.as_mut(), env, mock_info("alice", &[]), ExecuteMsg::EmergencyUnstake {}).unwrap_err();
+        assert_eq!(err, ContractError::NoStaked {});
+    }
+}
@@ -14,6 +14,12 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("No pending ownership proposal")]
+    NoOwnerProposal {},
+
+    #[error("Ownership proposal expired at {expiry}")]
+    OwnerProposalExpired { expiry: Expiration },
+
     #[error("InvalidInput")]
     InvalidInput {},
 
@@ -29,6 +35,9 @@ pub enum ContractError {
     #[error("No Staked")]
     NoStaked {},
 
+    #[error("{action} paused by the guardian")]
+    Paused { action: String },
+
     #[error("Not enough bFOT, needs {bfot_accept_amount}")]
     NotEnoughbFOT { bfot_accept_amount:Uint128 },
 
@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cw20::{Cw20ReceiveMsg};
 use cosmwasm_std::{Uint128, Addr};
+use cw_utils::Expiration;
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InstantiateMsg {
@@ -11,7 +12,9 @@ pub struct InstantiateMsg {
     pub bfot_token_address: Addr,
     pub gfot_token_address: Addr,
     pub daily_fot_amount: Uint128,
-    pub apy_prefix: Uint128
+    pub apy_prefix: Uint128,
+    /// Can pause and unpause besides the owner, none if not set.
+    pub guardian: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,11 +27,17 @@ pub struct StakerInfo {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    UpdateConfig {
-        /// NewOwner if non sent, contract gets locked. Recipients can receive airdrops
-        /// but owner cannot register new stages.
-        new_owner: Option<String>,
+    /// Offers ownership to the address, it takes over once it accepts
+    ProposeOwner {
+        address: String,
+        /// Proposal never expires if not set
+        expiry: Option<Expiration>,
     },
+    AcceptOwnership {},
+    /// Drops the pending proposal
+    CancelProposal {},
+    /// Leaves the contract without an owner for good
+    RenounceOwnership {},
     UpdateConstants {
         daily_fot_amount: Uint128,
         apy_prefix: Uint128,
@@ -38,6 +47,9 @@ pub enum ExecuteMsg {
     WithdrawGFot { },
     ClaimReward { },
     Unstake {},
+    /// Returns the staked gFOT without settling rewards, pending rewards are forfeited.
+    /// Works while paused.
+    EmergencyUnstake {},
     UpdateLastTime {
         last_time: u64
     },
@@ -50,19 +62,36 @@ pub enum ExecuteMsg {
     RemoveAllStakers {
         start_after: Option<String>,
         limit: Option<u32>
-    }
+    },
+    /// Owner only, removes the guardian if not set
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Halts the flagged actions, guardian or owner only
+    Pause {
+        flags: PauseFlags,
+    },
+    /// Resumes the flagged actions, guardian or owner only
+    Unpause {
+        flags: PauseFlags,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveMsg {
-    Stake {},
+    /// Stakes the sent gFOT for the staker, or for the sender if none set.
+    Stake {
+        staker: Option<String>,
+    },
     InitialFund {},
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Guardian and the paused actions
+    Status {},
     Staker {
         address: Addr
     },
@@ -86,10 +115,35 @@ pub struct ConfigResponse {
     pub gfot_amount: Uint128,
     pub last_time: u64,
     pub daily_fot_amount: Uint128,
-    pub apy_prefix: Uint128
+    pub apy_prefix: Uint128,
+    pub pending_owner: Option<OwnerProposal>
+}
 
+
+/// Actions the guardian can halt. Flags not set are left alone by Pause and Unpause.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseFlags {
+    /// gFOT stakes
+    #[serde(default)]
+    pub stakes: bool,
+    /// FOT reward claims
+    #[serde(default)]
+    pub claims: bool
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct StatusResponse {
+    pub guardian: Option<String>,
+    pub paused: PauseFlags
+}
+
+/// Owner proposed by the current one, waiting to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerProposal {
+    pub owner: Addr,
+    pub expiry: Expiration
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map};
 
+use crate::msg::{OwnerProposal, PauseFlags};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -25,3 +26,17 @@ pub const CONFIG: Item<Config> = Item::new(CONFIG_KEY);
 
 pub const STAKERS_KEY: &str = "stakers";
 pub const STAKERS: Map<Addr, (Uint128, Uint128)> = Map::new(STAKERS_KEY);
+
+pub const OWNER_PROPOSAL_KEY: &str = "owner_proposal";
+pub const OWNER_PROPOSAL: Item<OwnerProposal> = Item::new(OWNER_PROPOSAL_KEY);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseState {
+    /// Can pause and unpause besides the owner
+    pub guardian: Option<Addr>,
+    pub paused: PauseFlags
+}
+
+pub const PAUSE_KEY: &str = "pause";
+/// Missing on deployments from before pausing, read as nothing paused
+pub const PAUSE: Item<PauseState> = Item::new(PAUSE_KEY);